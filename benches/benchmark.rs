@@ -1,5 +1,6 @@
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
-use twine::Twine;
+use std::fmt::Write;
+use twine::{twine, Twine};
 
 pub fn criterion_benchmark(c: &mut Criterion) {
     let mut g_str_str_short = c.benchmark_group("short str concat");
@@ -155,6 +156,12 @@ pub fn criterion_benchmark(c: &mut Criterion) {
             let _ = black_box(r);
         });
     });
+    g_str_u32.bench_with_input("twine!: concat + to_string", &str_u32, |bench, &(a, b)| {
+        bench.iter(|| {
+            let r = twine!(a, b).to_string();
+            let _ = black_box(r);
+        });
+    });
     g_str_u32.bench_with_input("String: format", &str_u32, |bench, &(a, b)| {
         bench.iter(|| {
             let r = format!("{a}{b}");
@@ -177,6 +184,55 @@ pub fn criterion_benchmark(c: &mut Criterion) {
         },
     );
     g_str_u32.finish();
+
+    let mut g_int_render = c.benchmark_group("integer rendering");
+    let ints = (&12_345_678_901_234_567_890u64, &-1_234_567i32);
+    g_int_render.bench_with_input("Twine: write_to u64", &ints, |bench, &(a, _)| {
+        let mut s = String::with_capacity(32);
+        bench.iter(|| {
+            s.clear();
+            let t = Twine::from(a);
+            let _ = black_box(t.write_to(&mut s));
+        });
+    });
+    g_int_render.bench_with_input("core::fmt: write! u64", &ints, |bench, &(a, _)| {
+        let mut s = String::with_capacity(32);
+        bench.iter(|| {
+            s.clear();
+            let _ = black_box(write!(s, "{a}"));
+        });
+    });
+    g_int_render.bench_with_input("Twine: write_to i32", &ints, |bench, &(_, b)| {
+        let mut s = String::with_capacity(32);
+        bench.iter(|| {
+            s.clear();
+            let t = Twine::from(b);
+            let _ = black_box(t.write_to(&mut s));
+        });
+    });
+    g_int_render.bench_with_input("core::fmt: write! i32", &ints, |bench, &(_, b)| {
+        let mut s = String::with_capacity(32);
+        bench.iter(|| {
+            s.clear();
+            let _ = black_box(write!(s, "{b}"));
+        });
+    });
+    g_int_render.bench_with_input("Twine: write_to hex u64", &ints, |bench, &(a, _)| {
+        let mut s = String::with_capacity(32);
+        bench.iter(|| {
+            s.clear();
+            let t = Twine::hex_u64(a);
+            let _ = black_box(t.write_to(&mut s));
+        });
+    });
+    g_int_render.bench_with_input("core::fmt: write! hex u64", &ints, |bench, &(a, _)| {
+        let mut s = String::with_capacity(32);
+        bench.iter(|| {
+            s.clear();
+            let _ = black_box(write!(s, "{a:x}"));
+        });
+    });
+    g_int_render.finish();
 }
 
 criterion_group!(benches, criterion_benchmark);
@@ -0,0 +1,107 @@
+//! Buffers that Twines can be rendered into when they can not be borrowed as a single str.
+
+/// A reusable buffer a Twine can be rendered into, see `Twine::to_str_ref`.
+pub trait Buffer: core::fmt::Write {
+    /// Remove all content from the buffer.
+    fn clear(&mut self);
+
+    /// The content of the buffer.
+    fn as_str(&self) -> &str;
+}
+
+#[cfg(feature = "alloc")]
+impl Buffer for alloc::string::String {
+    #[inline(always)]
+    fn clear(&mut self) {
+        alloc::string::String::clear(self);
+    }
+
+    #[inline(always)]
+    fn as_str(&self) -> &str {
+        alloc::string::String::as_str(self)
+    }
+}
+
+/// A fixed-capacity buffer of `N` bytes that lives on the stack.
+///
+/// Writing more than `N` bytes fails with an `fmt::Error`.
+/// Writes are never split, so the buffer only ever contains whole strs.
+///
+/// # Example
+/// ```
+/// # use twine::{Buffer, StackBuffer};
+/// use core::fmt::Write;
+/// let mut buf = StackBuffer::<8>::new();
+/// assert!(buf.write_str("1234").is_ok());
+/// assert!(buf.write_str("56789").is_err());
+/// assert_eq!(buf.as_str(), "1234");
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct StackBuffer<const N: usize> {
+    bytes: [u8; N],
+    len: usize,
+}
+
+impl<const N: usize> StackBuffer<N> {
+    /// Create a new empty buffer.
+    #[inline(always)]
+    #[must_use]
+    pub fn new() -> Self {
+        StackBuffer {
+            bytes: [0; N],
+            len: 0,
+        }
+    }
+
+    /// The number of bytes in the buffer.
+    #[inline(always)]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Checks if the buffer is empty.
+    #[inline(always)]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The content of the buffer as bytes.
+    #[inline(always)]
+    #[must_use]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes[..self.len]
+    }
+}
+
+impl<const N: usize> Default for StackBuffer<N> {
+    #[inline(always)]
+    fn default() -> Self {
+        StackBuffer::new()
+    }
+}
+
+impl<const N: usize> core::fmt::Write for StackBuffer<N> {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        let end = self.len + s.len();
+        if end > N {
+            return Err(core::fmt::Error);
+        }
+        self.bytes[self.len..end].copy_from_slice(s.as_bytes());
+        self.len = end;
+        Ok(())
+    }
+}
+
+impl<const N: usize> Buffer for StackBuffer<N> {
+    #[inline(always)]
+    fn clear(&mut self) {
+        self.len = 0;
+    }
+
+    #[inline(always)]
+    fn as_str(&self) -> &str {
+        core::str::from_utf8(self.as_bytes()).expect("only whole strs are written")
+    }
+}
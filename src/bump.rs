@@ -0,0 +1,164 @@
+//! Building and rendering Twines in a `bumpalo::Bump` arena.
+
+use bumpalo::Bump;
+
+use crate::{Twine, TwinePiece};
+
+/// Builds Twines whose nodes and leaf values are allocated in a `Bump` arena.
+///
+/// All Twines returned by the builder live as long as the arena,
+/// so they can be stored and concatenated freely without binding every piece to a local variable.
+/// Values are moved into the arena, which never runs destructors,
+/// so only `Copy` values like integers, chars, floats, strs and other Twines are accepted.
+///
+/// # Example
+/// ```
+/// # use twine::{Twine, TwineArena};
+/// let bump = bumpalo::Bump::new();
+/// let arena = TwineArena::new(&bump);
+/// let mut t = arena.str("registers:");
+/// for register in 0..4u8 {
+///     t = arena.concat(t, arena.piece(' '));
+///     t = arena.concat(t, arena.twine(Twine::hex(arena.value(register * 8))));
+/// }
+/// assert_eq!(t.to_bump_str(&bump), "registers: 0 8 10 18");
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct TwineArena<'bump> {
+    bump: &'bump Bump,
+}
+
+impl<'bump> TwineArena<'bump> {
+    /// Create a new builder that allocates in the given arena.
+    #[inline(always)]
+    #[must_use]
+    pub fn new(bump: &'bump Bump) -> Self {
+        TwineArena { bump }
+    }
+
+    /// The arena the builder allocates in.
+    #[inline(always)]
+    #[must_use]
+    pub fn bump(&self) -> &'bump Bump {
+        self.bump
+    }
+
+    /// Move a value into the arena, e.g. to pass it to a Twine constructor like `Twine::hex`.
+    #[inline(always)]
+    pub fn value<T: Copy>(&self, value: T) -> &'bump T {
+        self.bump.alloc(value)
+    }
+
+    /// Move a Twine into the arena.
+    #[inline(always)]
+    pub fn twine(&self, t: Twine<'bump>) -> &'bump Twine<'bump> {
+        self.bump.alloc(t)
+    }
+
+    /// Copy a str into the arena and create a Twine of it.
+    ///
+    /// # Example
+    /// ```
+    /// # use twine::TwineArena;
+    /// let bump = bumpalo::Bump::new();
+    /// let arena = TwineArena::new(&bump);
+    /// let name = String::from("temporary");
+    /// let t = arena.str(&name);
+    /// drop(name);
+    /// assert_eq!(t.to_string(), "temporary");
+    /// ```
+    #[inline]
+    pub fn str(&self, s: &str) -> &'bump Twine<'bump> {
+        self.twine(Twine::from(&*self.bump.alloc_str(s)))
+    }
+
+    /// Move a value into the arena and create a Twine of it.
+    ///
+    /// Accepts the same values as the `twine!` macro, as long as they are `Copy`.
+    ///
+    /// # Example
+    /// ```
+    /// # use twine::TwineArena;
+    /// let bump = bumpalo::Bump::new();
+    /// let arena = TwineArena::new(&bump);
+    /// let t = arena.concat(arena.piece(-12i64), arena.piece(0.5f32));
+    /// assert_eq!(t.to_string(), "-120.5");
+    /// ```
+    #[inline]
+    pub fn piece<T: TwinePiece + Copy + 'bump>(&self, value: T) -> &'bump Twine<'bump> {
+        self.twine(self.value(value).to_twine())
+    }
+
+    /// Create a Twine by concatenating two Twines of the arena.
+    #[inline]
+    pub fn concat(
+        &self,
+        lhs: &'bump Twine<'bump>,
+        rhs: &'bump Twine<'bump>,
+    ) -> &'bump Twine<'bump> {
+        self.twine(lhs + rhs)
+    }
+}
+
+impl<'a> Twine<'a> {
+    /// Render the Twine into a str allocated in the arena.
+    ///
+    /// The exact length is allocated up front, so the str is never reallocated.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if it fails to write to the String it allocates.
+    ///
+    /// # Example
+    /// ```
+    /// # use twine::Twine;
+    /// let bump = bumpalo::Bump::new();
+    /// let a = &Twine::from("1234");
+    /// let b = &Twine::from(&56u32);
+    /// assert_eq!(a.concat(b).to_bump_str(&bump), "123456");
+    /// ```
+    #[must_use]
+    pub fn to_bump_str<'b>(&self, bump: &'b Bump) -> &'b str {
+        if let Some(s) = self.as_single_str() {
+            return bump.alloc_str(s);
+        }
+        let mut s = bumpalo::collections::String::with_capacity_in(self.len(), bump);
+        self.write_to(&mut s).expect("could not format into String");
+        s.into_bump_str()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn arena_outlives_values() {
+        let bump = Bump::new();
+        let arena = TwineArena::new(&bump);
+        let t = {
+            let name = std::string::String::from("x");
+            let value = 3i32;
+            let lhs = arena.concat(arena.str(&name), arena.piece('='));
+            arena.concat(lhs, arena.piece(value))
+        };
+        assert_eq!(t.to_bump_str(&bump), "x=3");
+        let null = arena.concat(t, arena.twine(Twine::null()));
+        assert!(null.is_null());
+        assert_eq!(null.to_bump_str(&bump), "");
+    }
+
+    #[test]
+    fn deep_arena_chain() {
+        const DEPTH: u32 = 10_000;
+        let bump = Bump::new();
+        let arena = TwineArena::new(&bump);
+        let mut t = arena.twine(Twine::empty());
+        for i in 0..DEPTH {
+            t = arena.concat(t, arena.piece(i % 10));
+        }
+        let s = t.to_bump_str(&bump);
+        assert_eq!(s.len(), DEPTH as usize);
+        assert!(s.starts_with("0123456789"));
+    }
+}
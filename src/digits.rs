@@ -0,0 +1,377 @@
+//! Allocation-free rendering of integers without `core::fmt`.
+//!
+//! Integers are rendered into a small stack buffer from the back to the front,
+//! so the digits can be written with a single `write_str` call.
+//! Only integers with padding or separators are written in several pieces.
+
+use crate::int::IntFormat;
+
+/// Maximum number of bytes of a rendered decimal integer: `u128::MAX` is 39 and `i128::MIN` is 40 bytes long.
+const DEC_BUF_LEN: usize = 40;
+
+/// Maximum number of bytes of an integer rendered in any radix: `u128::MAX` is 128 bytes long in binary.
+const RADIX_BUF_LEN: usize = 128;
+
+/// The largest power of 10 that fits into a u64.
+const U64_MAX_POW10: u64 = 10_000_000_000_000_000_000;
+
+/// Number of decimal digits of `U64_MAX_POW10 - 1`.
+const U64_MAX_POW10_DIGITS: usize = 19;
+
+/// All pairs of decimal digits from "00" to "99".
+const DEC_DIGITS_LUT: &[u8; 200] = b"\
+    0001020304050607080910111213141516171819\
+    2021222324252627282930313233343536373839\
+    4041424344454647484950515253545556575859\
+    6061626364656667686970717273747576777879\
+    8081828384858687888990919293949596979899";
+
+/// All lowercase digits up to radix 36.
+const RADIX_DIGITS_LUT: &[u8; 36] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+
+/// All uppercase digits up to radix 36.
+const UPPER_RADIX_DIGITS_LUT: &[u8; 36] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+/// Size of the buffer used to batch the pieces of formatted integers.
+const BATCH_LEN: usize = 64;
+
+/// Copy the two decimal digits of `n < 100` into `buf[curr..curr + 2]`
+#[inline(always)]
+fn put_dec_pair(buf: &mut [u8], curr: usize, n: usize) {
+    let d = n * 2;
+    buf[curr..curr + 2].copy_from_slice(&DEC_DIGITS_LUT[d..d + 2]);
+}
+
+/// Convert the rendered ASCII digits into a str
+#[inline(always)]
+fn ascii_str(digits: &[u8]) -> &str {
+    core::str::from_utf8(digits).expect("rendered digits are always ASCII")
+}
+
+/// Render the decimal digits of `n` in front of `buf[..curr]` and return the new start.
+fn format_dec_u64(buf: &mut [u8], mut curr: usize, mut n: u64) -> usize {
+    while n >= 10_000 {
+        let rem = (n % 10_000) as usize;
+        n /= 10_000;
+        curr -= 4;
+        put_dec_pair(buf, curr, rem / 100);
+        put_dec_pair(buf, curr + 2, rem % 100);
+    }
+    // n < 10_000 now fits into usize on every platform
+    let mut n = n as usize;
+    if n >= 100 {
+        curr -= 2;
+        put_dec_pair(buf, curr, n % 100);
+        n /= 100;
+    }
+    if n < 10 {
+        curr -= 1;
+        buf[curr] = b'0' + n as u8;
+    } else {
+        curr -= 2;
+        put_dec_pair(buf, curr, n);
+    }
+    curr
+}
+
+/// Render the decimal digits of `n`, prefixed with a minus sign if `negative`.
+fn format_dec(buf: &mut [u8; DEC_BUF_LEN], mut n: u128, negative: bool) -> &str {
+    let mut curr = buf.len();
+    // render chunks of 19 digits with the cheaper u64 arithmetic
+    while n > u128::from(u64::MAX) {
+        let rem = (n % u128::from(U64_MAX_POW10)) as u64;
+        n /= u128::from(U64_MAX_POW10);
+        let start = curr - U64_MAX_POW10_DIGITS;
+        let digits_start = format_dec_u64(buf, curr, rem);
+        buf[start..digits_start].fill(b'0');
+        curr = start;
+    }
+    curr = format_dec_u64(buf, curr, n as u64);
+    if negative {
+        curr -= 1;
+        buf[curr] = b'-';
+    }
+    ascii_str(&buf[curr..])
+}
+
+/// Render the digits of `n` in the given radix.
+fn format_radix(buf: &mut [u8; RADIX_BUF_LEN], mut n: u128, radix: u8, uppercase: bool) -> &str {
+    debug_assert!((2..=36).contains(&radix));
+    let lut = if uppercase {
+        UPPER_RADIX_DIGITS_LUT
+    } else {
+        RADIX_DIGITS_LUT
+    };
+    let mut curr = buf.len();
+    if radix.is_power_of_two() {
+        // cheap shifts instead of divisions for binary, octal and hexadecimal
+        let shift = radix.trailing_zeros();
+        let mask = u128::from(radix - 1);
+        loop {
+            curr -= 1;
+            buf[curr] = lut[(n & mask) as usize];
+            n >>= shift;
+            if n == 0 {
+                break;
+            }
+        }
+    } else {
+        let radix = u128::from(radix);
+        loop {
+            curr -= 1;
+            buf[curr] = lut[(n % radix) as usize];
+            n /= radix;
+            if n == 0 {
+                break;
+            }
+        }
+    }
+    ascii_str(&buf[curr..])
+}
+
+/// The prefix of integers rendered in the given radix, if there is a common one.
+#[inline(always)]
+fn radix_prefix(radix: u8) -> &'static str {
+    match radix {
+        2 => "0b",
+        8 => "0o",
+        16 => "0x",
+        _ => "",
+    }
+}
+
+/// Write the decimal representation of an integer given by its absolute value and sign.
+#[inline]
+pub(crate) fn write_dec<W: core::fmt::Write>(
+    w: &mut W,
+    n: u128,
+    negative: bool,
+) -> core::fmt::Result {
+    let mut buf = [0u8; DEC_BUF_LEN];
+    w.write_str(format_dec(&mut buf, n, negative))
+}
+
+/// Collects the pieces of a formatted integer to write them with few `write_str` calls.
+struct Batch<'w, W: core::fmt::Write> {
+    w: &'w mut W,
+    buf: [u8; BATCH_LEN],
+    len: usize,
+}
+
+impl<'w, W: core::fmt::Write> Batch<'w, W> {
+    fn new(w: &'w mut W) -> Self {
+        Batch {
+            w,
+            buf: [0; BATCH_LEN],
+            len: 0,
+        }
+    }
+
+    fn push(&mut self, s: &str) -> core::fmt::Result {
+        if self.len + s.len() > BATCH_LEN {
+            self.flush()?;
+        }
+        if s.len() > BATCH_LEN {
+            return self.w.write_str(s);
+        }
+        self.buf[self.len..self.len + s.len()].copy_from_slice(s.as_bytes());
+        self.len += s.len();
+        Ok(())
+    }
+
+    fn flush(&mut self) -> core::fmt::Result {
+        if self.len > 0 {
+            let s =
+                core::str::from_utf8(&self.buf[..self.len]).expect("only whole strs are pushed");
+            self.w.write_str(s)?;
+            self.len = 0;
+        }
+        Ok(())
+    }
+}
+
+/// Write the rendered `digits` with the sign, padding, prefix and separators of `format`.
+fn write_formatted<W: core::fmt::Write>(
+    w: &mut W,
+    digits: &str,
+    sign: &str,
+    prefix: &str,
+    format: IntFormat,
+) -> core::fmt::Result {
+    let total = digits.len().max(format.min_digits());
+    let pad = total - digits.len();
+    let zero_pad = format.is_zero_pad();
+    let group = format.group();
+    let mut separator_buf = [0u8; 4];
+    let separator = group.map_or("", |(separator, _)| {
+        separator.encode_utf8(&mut separator_buf)
+    });
+    // checks if there is a separator in front of the digit at index `i`
+    let is_separated =
+        |i: usize| matches!(group, Some((_, group)) if i > 0 && (total - i).is_multiple_of(group));
+
+    let mut out = Batch::new(w);
+    if !zero_pad {
+        // replace the padding digits and the separators after them with spaces
+        for i in 0..pad {
+            out.push(" ")?;
+            if is_separated(i + 1) {
+                out.push(" ")?;
+            }
+        }
+    }
+    out.push(sign)?;
+    if format.has_prefix() {
+        out.push(prefix)?;
+    }
+    for i in 0..total {
+        if i < pad && !zero_pad {
+            continue;
+        }
+        if is_separated(i) && (zero_pad || i > pad) {
+            out.push(separator)?;
+        }
+        if i < pad {
+            out.push("0")?;
+        } else {
+            out.push(&digits[i - pad..=i - pad])?;
+        }
+    }
+    out.flush()
+}
+
+/// The length of `digits` rendered digits with the sign, padding, prefix and separators of `format`.
+fn formatted_len(digits: usize, sign: usize, prefix: usize, format: IntFormat) -> usize {
+    let total = digits.max(format.min_digits());
+    let prefix = if format.has_prefix() { prefix } else { 0 };
+    let separators = match format.group() {
+        None => 0,
+        Some((separator, group)) => {
+            let digit_separators = (digits - 1) / group;
+            let pad_separators = (total - 1) / group - digit_separators;
+            // separators between padding digits are replaced by a single space each
+            let pad_separators_len = if format.is_zero_pad() {
+                pad_separators * separator.len_utf8()
+            } else {
+                pad_separators
+            };
+            digit_separators * separator.len_utf8() + pad_separators_len
+        }
+    };
+    sign + prefix + total + separators
+}
+
+/// Write the representation of an integer given by its absolute value and sign
+/// in the given radix with the given formatting options.
+#[inline]
+pub(crate) fn write_radix<W: core::fmt::Write>(
+    w: &mut W,
+    n: u128,
+    negative: bool,
+    radix: u8,
+    format: IntFormat,
+) -> core::fmt::Result {
+    let mut buf = [0u8; RADIX_BUF_LEN];
+    let digits = format_radix(&mut buf, n, radix, format.is_uppercase());
+    let sign = if negative { "-" } else { "" };
+    if format.is_plain() {
+        w.write_str(sign)?;
+        w.write_str(digits)
+    } else {
+        write_formatted(w, digits, sign, radix_prefix(radix), format)
+    }
+}
+
+/// Number of decimal digits of `n`
+#[inline]
+pub(crate) fn dec_len(n: u128) -> usize {
+    match u64::try_from(n) {
+        Ok(n) => n.checked_ilog10().map_or(1, |digits| digits as usize + 1),
+        Err(_) => n.ilog10() as usize + 1,
+    }
+}
+
+/// Length of the representation of an integer given by its absolute value and sign
+/// in the given radix with the given formatting options.
+#[inline]
+pub(crate) fn radix_len(n: u128, negative: bool, radix: u8, format: IntFormat) -> usize {
+    let digits = n
+        .checked_ilog(u128::from(radix))
+        .map_or(1, |digits| digits as usize + 1);
+    let sign = usize::from(negative);
+    if format.is_plain() {
+        sign + digits
+    } else {
+        formatted_len(digits, sign, radix_prefix(radix).len(), format)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn dec_matches_core_fmt() {
+        let mut buf = [0u8; DEC_BUF_LEN];
+        let mut n = 1u128;
+        while let Some(next) = n.checked_mul(3) {
+            for x in [n - 1, n, n + 1] {
+                assert_eq!(format_dec(&mut buf, x, false), std::format!("{x}"));
+                assert_eq!(format_dec(&mut buf, x, true), std::format!("-{x}"));
+            }
+            n = next;
+        }
+        for x in [0, 9, 10, 99, 100, 999, 1000, 9999, 10_000, u128::MAX] {
+            assert_eq!(format_dec(&mut buf, x, false), std::format!("{x}"));
+        }
+        for x in [
+            u128::from(u64::MAX),
+            u128::from(u64::MAX) + 1,
+            10u128.pow(38),
+        ] {
+            assert_eq!(format_dec(&mut buf, x, false), std::format!("{x}"));
+            assert_eq!(dec_len(x), std::format!("{x}").len());
+        }
+        let x = i128::MIN;
+        assert_eq!(
+            format_dec(&mut buf, x.unsigned_abs(), true),
+            std::format!("{x}")
+        );
+    }
+
+    #[test]
+    fn radix_matches_core_fmt() {
+        let mut buf = [0u8; RADIX_BUF_LEN];
+        for x in [
+            0,
+            1,
+            0xf,
+            0x10,
+            0xabc,
+            0xdead_beef,
+            u128::MAX / 3,
+            u128::MAX,
+        ] {
+            let plain = IntFormat::new();
+            assert_eq!(format_radix(&mut buf, x, 16, false), std::format!("{x:x}"));
+            assert_eq!(format_radix(&mut buf, x, 16, true), std::format!("{x:X}"));
+            assert_eq!(format_radix(&mut buf, x, 8, false), std::format!("{x:o}"));
+            assert_eq!(format_radix(&mut buf, x, 2, false), std::format!("{x:b}"));
+            assert_eq!(radix_len(x, false, 16, plain), std::format!("{x:x}").len());
+            assert_eq!(radix_len(x, false, 8, plain), std::format!("{x:o}").len());
+            assert_eq!(radix_len(x, false, 2, plain), std::format!("{x:b}").len());
+        }
+    }
+
+    #[test]
+    fn radix_round_trips() {
+        let mut buf = [0u8; RADIX_BUF_LEN];
+        for radix in 2..=36 {
+            for x in [0, 1, 35, 36, 1295, 1296, u128::from(u64::MAX), u128::MAX] {
+                let digits = format_radix(&mut buf, x, radix, radix % 2 == 0);
+                assert_eq!(u128::from_str_radix(digits, u32::from(radix)), Ok(x));
+                assert_eq!(radix_len(x, false, radix, IntFormat::new()), digits.len());
+            }
+        }
+    }
+}
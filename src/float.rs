@@ -0,0 +1,221 @@
+//! Floating point children of Twines.
+//!
+//! Floats are rendered with `core::fmt`, as shortest round-trip representations
+//! can not be computed without considerable effort.
+
+use crate::sealed;
+
+/// Reference to a floating point number of any width, see `crate::sealed`.
+#[derive(Debug, Clone, Copy)]
+pub enum FloatRef<'a> {
+    F32(&'a f32),
+    F64(&'a f64),
+}
+
+/// How a floating point number is rendered.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum FloatFormat {
+    /// The shortest representation that round-trips, like the `{}` format.
+    Shortest,
+    /// The given number of digits after the decimal point, like the `{:.precision$}` format.
+    Fixed(u16),
+    /// The shortest representation that round-trips in scientific notation, like the `{:e}` format.
+    Exp,
+}
+
+/// Render a float in the given format with `core::fmt`
+#[inline]
+fn write_float<W, T>(w: &mut W, x: T, format: FloatFormat) -> core::fmt::Result
+where
+    W: core::fmt::Write,
+    T: core::fmt::Display + core::fmt::LowerExp,
+{
+    match format {
+        FloatFormat::Shortest => write!(w, "{x}"),
+        FloatFormat::Fixed(precision) => {
+            write!(w, "{x:.precision$}", precision = usize::from(precision))
+        }
+        FloatFormat::Exp => write!(w, "{x:e}"),
+    }
+}
+
+/// A lower bound of the decimal exponent of a finite, non-zero `x`.
+///
+/// The actual decimal exponent is either the returned value or one more.
+fn dec_exponent_lower_bound(x: f64) -> i32 {
+    const MANTISSA_BITS: u32 = 52;
+    const EXPONENT_BIAS: i32 = 1023;
+    let bits = x.to_bits();
+    let exponent_bits = ((bits >> MANTISSA_BITS) & 0x7ff) as i32;
+    let bin_exponent = if exponent_bits == 0 {
+        // subnormal numbers have an implicit exponent of 1 and no implicit leading 1 bit
+        let mantissa = bits & ((1 << MANTISSA_BITS) - 1);
+        1 - EXPONENT_BIAS - (mantissa.leading_zeros() - (63 - MANTISSA_BITS)) as i32
+    } else {
+        exponent_bits - EXPONENT_BIAS
+    };
+    // floor(bin_exponent * log10(2)), exact for all exponents of f64
+    (bin_exponent * 78_913) >> 18
+}
+
+/// Number of decimal digits of `n`
+#[inline(always)]
+fn digits(n: u32) -> usize {
+    n.checked_ilog10().map_or(1, |digits| digits as usize + 1)
+}
+
+impl<'a> FloatRef<'a> {
+    /// The value of the float, f32 values are converted losslessly.
+    #[inline(always)]
+    fn value(self) -> f64 {
+        match self {
+            FloatRef::F32(x) => f64::from(*x),
+            FloatRef::F64(x) => *x,
+        }
+    }
+
+    /// Render the float in the given format.
+    #[inline]
+    pub(crate) fn write_to<W: core::fmt::Write>(
+        self,
+        w: &mut W,
+        format: FloatFormat,
+    ) -> core::fmt::Result {
+        match self {
+            FloatRef::F32(x) => write_float(w, *x, format),
+            FloatRef::F64(x) => write_float(w, *x, format),
+        }
+    }
+
+    /// A lower bound of the length of the rendered float, computed without rendering it.
+    pub(crate) fn estimated_len(self, format: FloatFormat) -> usize {
+        let x = self.value();
+        if x.is_nan() {
+            return 3;
+        }
+        let sign = usize::from(x.is_sign_negative());
+        if x.is_infinite() {
+            return sign + 3;
+        }
+        let exponent = if x == 0.0 {
+            0
+        } else {
+            dec_exponent_lower_bound(x)
+        };
+        sign + match format {
+            // the integer digits or "0." followed by the zeros after the decimal point and a digit
+            FloatFormat::Shortest if exponent >= 0 => exponent as usize + 1,
+            FloatFormat::Shortest => 2 + (exponent + 1).unsigned_abs().max(1) as usize,
+            // the integer digits, the decimal point and the precision
+            FloatFormat::Fixed(precision) => {
+                let integer = if exponent >= 0 {
+                    exponent as usize + 1
+                } else {
+                    1
+                };
+                let fraction = if precision > 0 {
+                    1 + usize::from(precision)
+                } else {
+                    0
+                };
+                integer + fraction
+            }
+            // a single digit, the 'e' and the exponent
+            FloatFormat::Exp => {
+                let negative_exponent = usize::from(exponent + 1 < 0);
+                let exponent_digits =
+                    digits(exponent.unsigned_abs().min((exponent + 1).unsigned_abs()));
+                2 + negative_exponent + exponent_digits
+            }
+        }
+    }
+}
+
+/// Floating point types that can be rendered by a Twine.
+///
+/// This trait is sealed and can not be implemented outside of this crate.
+pub trait Float: sealed::Float {}
+
+impl sealed::Float for f32 {
+    #[inline(always)]
+    fn float_ref(&self) -> FloatRef<'_> {
+        FloatRef::F32(self)
+    }
+}
+
+impl Float for f32 {}
+
+impl sealed::Float for f64 {
+    #[inline(always)]
+    fn float_ref(&self) -> FloatRef<'_> {
+        FloatRef::F64(self)
+    }
+}
+
+impl Float for f64 {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn estimated_len_is_lower_bound() {
+        let mut values = std::vec![
+            0.0,
+            -0.0,
+            1.0,
+            -1.0,
+            0.1,
+            9.99,
+            10.0,
+            123.456,
+            1e-7,
+            1.5e300,
+            -2.5e-300,
+            f64::MAX,
+            f64::MIN_POSITIVE,
+            f64::MIN_POSITIVE / 3.0,
+            5e-324,
+            f64::INFINITY,
+            f64::NEG_INFINITY,
+            f64::NAN,
+        ];
+        let mut x = 1.0;
+        for _ in 0..700 {
+            values.push(x);
+            values.push(1.0 / x);
+            x *= 2.7;
+        }
+        for x in values {
+            let float = FloatRef::F64(&x);
+            for format in [
+                FloatFormat::Shortest,
+                FloatFormat::Fixed(0),
+                FloatFormat::Fixed(3),
+                FloatFormat::Exp,
+            ] {
+                let mut s = std::string::String::new();
+                float.write_to(&mut s, format).unwrap();
+                assert!(float.estimated_len(format) <= s.len(), "{x} {format:?} {s}");
+            }
+        }
+    }
+
+    #[test]
+    fn dec_exponent() {
+        for (x, exponent) in [
+            (1.0, 0),
+            (9.99, 0),
+            (10.0, 1),
+            (0.5, -1),
+            (1e-7, -7),
+            (1e300, 300),
+        ] {
+            let lower_bound = dec_exponent_lower_bound(x);
+            assert!(
+                lower_bound == exponent || lower_bound + 1 == exponent,
+                "{x}"
+            );
+        }
+    }
+}
@@ -0,0 +1,325 @@
+//! Integer children of Twines of any width.
+
+use core::num::{
+    NonZeroI128, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8, NonZeroIsize, NonZeroU128,
+    NonZeroU16, NonZeroU32, NonZeroU64, NonZeroU8, NonZeroUsize,
+};
+
+use crate::sealed;
+
+/// Reference to an integer of any width, see `crate::sealed`.
+#[derive(Debug, Clone, Copy)]
+pub enum IntRef<'a> {
+    U8(&'a u8),
+    U16(&'a u16),
+    U32(&'a u32),
+    U64(&'a u64),
+    U128(&'a u128),
+    Usize(&'a usize),
+    I8(&'a i8),
+    I16(&'a i16),
+    I32(&'a i32),
+    I64(&'a i64),
+    I128(&'a i128),
+    Isize(&'a isize),
+    NonZeroU8(&'a NonZeroU8),
+    NonZeroU16(&'a NonZeroU16),
+    NonZeroU32(&'a NonZeroU32),
+    NonZeroU64(&'a NonZeroU64),
+    NonZeroU128(&'a NonZeroU128),
+    NonZeroUsize(&'a NonZeroUsize),
+    NonZeroI8(&'a NonZeroI8),
+    NonZeroI16(&'a NonZeroI16),
+    NonZeroI32(&'a NonZeroI32),
+    NonZeroI64(&'a NonZeroI64),
+    NonZeroI128(&'a NonZeroI128),
+    NonZeroIsize(&'a NonZeroIsize),
+}
+
+impl<'a> IntRef<'a> {
+    /// The absolute value of the integer and whether it is negative.
+    #[inline(always)]
+    pub(crate) fn unsigned_abs(self) -> (u128, bool) {
+        match self {
+            IntRef::U8(x) => (u128::from(*x), false),
+            IntRef::U16(x) => (u128::from(*x), false),
+            IntRef::U32(x) => (u128::from(*x), false),
+            IntRef::U64(x) => (u128::from(*x), false),
+            IntRef::U128(x) => (*x, false),
+            IntRef::Usize(x) => (*x as u128, false),
+            IntRef::I8(x) => (u128::from(x.unsigned_abs()), *x < 0),
+            IntRef::I16(x) => (u128::from(x.unsigned_abs()), *x < 0),
+            IntRef::I32(x) => (u128::from(x.unsigned_abs()), *x < 0),
+            IntRef::I64(x) => (u128::from(x.unsigned_abs()), *x < 0),
+            IntRef::I128(x) => (x.unsigned_abs(), *x < 0),
+            IntRef::Isize(x) => (x.unsigned_abs() as u128, *x < 0),
+            IntRef::NonZeroU8(x) => (u128::from(x.get()), false),
+            IntRef::NonZeroU16(x) => (u128::from(x.get()), false),
+            IntRef::NonZeroU32(x) => (u128::from(x.get()), false),
+            IntRef::NonZeroU64(x) => (u128::from(x.get()), false),
+            IntRef::NonZeroU128(x) => (x.get(), false),
+            IntRef::NonZeroUsize(x) => (x.get() as u128, false),
+            IntRef::NonZeroI8(x) => (u128::from(x.get().unsigned_abs()), x.get() < 0),
+            IntRef::NonZeroI16(x) => (u128::from(x.get().unsigned_abs()), x.get() < 0),
+            IntRef::NonZeroI32(x) => (u128::from(x.get().unsigned_abs()), x.get() < 0),
+            IntRef::NonZeroI64(x) => (u128::from(x.get().unsigned_abs()), x.get() < 0),
+            IntRef::NonZeroI128(x) => (x.get().unsigned_abs(), x.get() < 0),
+            IntRef::NonZeroIsize(x) => (x.get().unsigned_abs() as u128, x.get() < 0),
+        }
+    }
+
+    /// The bits of the two's complement representation of the integer,
+    /// as rendered by the `{:x}` format of `core::fmt`.
+    #[inline(always)]
+    pub(crate) fn bits(self) -> u128 {
+        match self {
+            IntRef::U8(x) => u128::from(*x),
+            IntRef::U16(x) => u128::from(*x),
+            IntRef::U32(x) => u128::from(*x),
+            IntRef::U64(x) => u128::from(*x),
+            IntRef::U128(x) => *x,
+            IntRef::Usize(x) => *x as u128,
+            IntRef::I8(x) => u128::from(*x as u8),
+            IntRef::I16(x) => u128::from(*x as u16),
+            IntRef::I32(x) => u128::from(*x as u32),
+            IntRef::I64(x) => u128::from(*x as u64),
+            IntRef::I128(x) => *x as u128,
+            IntRef::Isize(x) => *x as usize as u128,
+            IntRef::NonZeroU8(x) => u128::from(x.get()),
+            IntRef::NonZeroU16(x) => u128::from(x.get()),
+            IntRef::NonZeroU32(x) => u128::from(x.get()),
+            IntRef::NonZeroU64(x) => u128::from(x.get()),
+            IntRef::NonZeroU128(x) => x.get(),
+            IntRef::NonZeroUsize(x) => x.get() as u128,
+            IntRef::NonZeroI8(x) => u128::from(x.get() as u8),
+            IntRef::NonZeroI16(x) => u128::from(x.get() as u16),
+            IntRef::NonZeroI32(x) => u128::from(x.get() as u32),
+            IntRef::NonZeroI64(x) => u128::from(x.get() as u64),
+            IntRef::NonZeroI128(x) => x.get() as u128,
+            IntRef::NonZeroIsize(x) => x.get() as usize as u128,
+        }
+    }
+
+    /// The value whose digits are rendered in the given radix and whether it is negative:
+    /// the two's complement bits in radix 2, 8 and 16,
+    /// the absolute value and the sign in all other radixes.
+    #[inline(always)]
+    pub(crate) fn radix_digits(self, radix: u8) -> (u128, bool) {
+        match radix {
+            2 | 8 | 16 => (self.bits(), false),
+            _ => self.unsigned_abs(),
+        }
+    }
+}
+
+/// Formatting options for rendering integers, see `Twine::hex_with` and `Twine::radix_with`.
+///
+/// The options are packed, so that a Twine with a formatted integer
+/// is not larger than a Twine with any other child.
+///
+/// # Example
+/// ```
+/// # use twine::{IntFormat, Twine};
+/// let format = IntFormat::new()
+///     .width(8)
+///     .zero_pad(true)
+///     .prefix(true)
+///     .separator('_', 4);
+/// assert_eq!(Twine::hex_with(&0x7fffu32, format).to_string(), "0x0000_7fff");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct IntFormat {
+    width: u8,
+    /// The flags in the lower bits and the group size in the upper bits.
+    flags: u8,
+    /// The separator char as bytes, as a `char` field would align and thereby enlarge the Twine.
+    separator: [u8; 4],
+}
+
+impl IntFormat {
+    const ZERO_PAD: u8 = 1 << 0;
+    const UPPERCASE: u8 = 1 << 1;
+    const PREFIX: u8 = 1 << 2;
+    const GROUP_SHIFT: u32 = 3;
+
+    /// The largest supported group size for digit separators.
+    pub const MAX_GROUP: u8 = u8::MAX >> IntFormat::GROUP_SHIFT;
+
+    /// Create new formatting options that render integers
+    /// like the plain `{:x}`, `{:o}` and `{:b}` formats, without padding, prefix or separators.
+    #[inline(always)]
+    #[must_use]
+    pub const fn new() -> Self {
+        IntFormat {
+            width: 0,
+            flags: 0,
+            separator: ('_' as u32).to_le_bytes(),
+        }
+    }
+
+    #[inline(always)]
+    const fn with_flag(mut self, flag: u8, enabled: bool) -> Self {
+        if enabled {
+            self.flags |= flag;
+        } else {
+            self.flags &= !flag;
+        }
+        self
+    }
+
+    /// Render at least `width` digits.
+    ///
+    /// Missing digits are filled with zeros if `zero_pad` is set.
+    /// Otherwise missing digits and their separators are replaced by spaces in front of the prefix,
+    /// so the integer is aligned to the right.
+    #[inline(always)]
+    #[must_use]
+    pub const fn width(mut self, width: u8) -> Self {
+        self.width = width;
+        self
+    }
+
+    /// Fill missing digits up to the `width` with zeros instead of spaces.
+    #[inline(always)]
+    #[must_use]
+    pub const fn zero_pad(self, zero_pad: bool) -> Self {
+        self.with_flag(IntFormat::ZERO_PAD, zero_pad)
+    }
+
+    /// Render uppercase instead of lowercase digits.
+    #[inline(always)]
+    #[must_use]
+    pub const fn uppercase(self, uppercase: bool) -> Self {
+        self.with_flag(IntFormat::UPPERCASE, uppercase)
+    }
+
+    /// Render the radix prefix `0b`, `0o` or `0x` in front of the digits.
+    ///
+    /// Radixes other than 2, 8 and 16 have no prefix.
+    #[inline(always)]
+    #[must_use]
+    pub const fn prefix(self, prefix: bool) -> Self {
+        self.with_flag(IntFormat::PREFIX, prefix)
+    }
+
+    /// Render `separator` between every `group` digits, counted from the right.
+    ///
+    /// A group of 0 disables the separator,
+    /// groups larger than `IntFormat::MAX_GROUP` are limited to `IntFormat::MAX_GROUP`.
+    #[inline(always)]
+    #[must_use]
+    pub const fn separator(mut self, separator: char, group: u8) -> Self {
+        let group = if group > IntFormat::MAX_GROUP {
+            IntFormat::MAX_GROUP
+        } else {
+            group
+        };
+        self.separator = (separator as u32).to_le_bytes();
+        self.flags = (self.flags & !(IntFormat::MAX_GROUP << IntFormat::GROUP_SHIFT))
+            | (group << IntFormat::GROUP_SHIFT);
+        self
+    }
+
+    /// The minimal number of digits.
+    #[inline(always)]
+    pub(crate) fn min_digits(self) -> usize {
+        usize::from(self.width)
+    }
+
+    #[inline(always)]
+    pub(crate) fn is_zero_pad(self) -> bool {
+        self.flags & IntFormat::ZERO_PAD != 0
+    }
+
+    #[inline(always)]
+    pub(crate) fn is_uppercase(self) -> bool {
+        self.flags & IntFormat::UPPERCASE != 0
+    }
+
+    #[inline(always)]
+    pub(crate) fn has_prefix(self) -> bool {
+        self.flags & IntFormat::PREFIX != 0
+    }
+
+    /// The separator and the number of digits between separators, if enabled.
+    #[inline(always)]
+    pub(crate) fn group(self) -> Option<(char, usize)> {
+        match self.flags >> IntFormat::GROUP_SHIFT {
+            0 => None,
+            group => {
+                let separator = char::from_u32(u32::from_le_bytes(self.separator))
+                    .expect("separator is stored from a char");
+                Some((separator, usize::from(group)))
+            }
+        }
+    }
+
+    /// Checks if the digits are rendered without padding, prefix or separators.
+    #[inline(always)]
+    pub(crate) fn is_plain(self) -> bool {
+        self.width == 0 && self.flags & !IntFormat::UPPERCASE == 0
+    }
+}
+
+impl Default for IntFormat {
+    #[inline(always)]
+    fn default() -> Self {
+        IntFormat::new()
+    }
+}
+
+/// Integer types of any width, including the `NonZero` integer types,
+/// that can be rendered by a Twine.
+///
+/// This trait is sealed and can not be implemented outside of this crate.
+pub trait Integer: sealed::Integer {}
+
+/// Implement `Integer` and `TwinePiece` for integer types referenced by the given `IntRef` variants
+macro_rules! impl_integer {
+    ($($t:ty => $variant:ident),* $(,)?) => {
+        $(
+            impl sealed::Integer for $t {
+                #[inline(always)]
+                fn int_ref(&self) -> IntRef<'_> {
+                    IntRef::$variant(self)
+                }
+            }
+
+            impl Integer for $t {}
+
+            impl crate::TwinePiece for $t {
+                #[inline(always)]
+                fn to_twine(&self) -> crate::Twine<'_> {
+                    crate::Twine::from(self)
+                }
+            }
+        )*
+    };
+}
+
+impl_integer! {
+    u8 => U8,
+    u16 => U16,
+    u32 => U32,
+    u64 => U64,
+    u128 => U128,
+    usize => Usize,
+    i8 => I8,
+    i16 => I16,
+    i32 => I32,
+    i64 => I64,
+    i128 => I128,
+    isize => Isize,
+    NonZeroU8 => NonZeroU8,
+    NonZeroU16 => NonZeroU16,
+    NonZeroU32 => NonZeroU32,
+    NonZeroU64 => NonZeroU64,
+    NonZeroU128 => NonZeroU128,
+    NonZeroUsize => NonZeroUsize,
+    NonZeroI8 => NonZeroI8,
+    NonZeroI16 => NonZeroI16,
+    NonZeroI32 => NonZeroI32,
+    NonZeroI64 => NonZeroI64,
+    NonZeroI128 => NonZeroI128,
+    NonZeroIsize => NonZeroIsize,
+}
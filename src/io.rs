@@ -0,0 +1,281 @@
+//! Rendering of Twines into `std::io::Write`.
+//!
+//! `Str` children are passed to the writer as they are, one `IoSlice` each.
+//! All other children are rendered into a small scratch buffer first,
+//! so that consecutive children can be written with a single `write_vectored` call.
+//! A child that does not fit into the rest of the scratch buffer is streamed to the writer,
+//! so every child is rendered once.
+
+use std::io::{self, IoSlice, Write};
+
+use crate::{Twine, TwineChild};
+
+/// Maximum number of slices passed to a single `write_vectored` call.
+const MAX_SLICES: usize = 32;
+
+/// Size of the scratch buffer for rendered non-str children.
+const SCRATCH_LEN: usize = 256;
+
+/// A piece of the Twine waiting to be written.
+#[derive(Debug, Clone, Copy)]
+enum Chunk<'a> {
+    /// A `Str` child which is written without copying it.
+    Str(&'a str),
+    /// A rendered child in the given range of the scratch buffer.
+    Scratch(usize, usize),
+}
+
+/// Error while writing a Twine to an `io::Write`.
+enum WriteIoError {
+    /// The Twine is nested deeper than `Twine::MAX_DEPTH`.
+    Depth,
+    Io(io::Error),
+}
+
+/// Collects the children of a Twine and writes them in batches of `IoSlice`s.
+struct VectoredWriter<'a, 'w, W: Write> {
+    w: &'w mut W,
+    chunks: [Chunk<'a>; MAX_SLICES],
+    len: usize,
+    scratch: [u8; SCRATCH_LEN],
+    scratch_len: usize,
+}
+
+impl<'a, 'w, W: Write> VectoredWriter<'a, 'w, W> {
+    fn new(w: &'w mut W) -> Self {
+        VectoredWriter {
+            w,
+            chunks: [Chunk::Str(""); MAX_SLICES],
+            len: 0,
+            scratch: [0; SCRATCH_LEN],
+            scratch_len: 0,
+        }
+    }
+
+    /// Add a leaf child of the Twine, writing all pending chunks if needed.
+    fn push(&mut self, child: &TwineChild<'a>) -> io::Result<()> {
+        if self.len == MAX_SLICES {
+            self.flush()?;
+        }
+        match child {
+            TwineChild::Str(s) => {
+                if !s.is_empty() {
+                    self.chunks[self.len] = Chunk::Str(s);
+                    self.len += 1;
+                }
+            }
+            TwineChild::FmtArgs(f) => {
+                self.flush()?;
+                self.w.write_fmt(**f)?;
+            }
+            #[cfg(feature = "alloc")]
+            TwineChild::Buf(buf) => buf.try_for_each_leaf(|child| self.push(child))?,
+            leaf => {
+                let start = self.scratch_len;
+                let mut spill = Spill {
+                    writer: self,
+                    start,
+                    streaming: false,
+                    error: None,
+                };
+                let rendered = leaf.write_to(&mut spill, crate::nested_depth(Twine::MAX_DEPTH));
+                let streaming = spill.streaming;
+                match (rendered, spill.error) {
+                    (_, Some(e)) => return Err(e),
+                    (Err(_), None) => return Err(io::Error::other("formatter error")),
+                    (Ok(()), None) if !streaming => self.push_scratch(start),
+                    (Ok(()), None) => {}
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Add the rendered bytes in the scratch buffer from `start` on as a chunk.
+    fn push_scratch(&mut self, start: usize) {
+        if start < self.scratch_len {
+            self.chunks[self.len] = Chunk::Scratch(start, self.scratch_len);
+            self.len += 1;
+        }
+    }
+
+    /// Write all pending chunks.
+    fn flush(&mut self) -> io::Result<()> {
+        let mut slices = [IoSlice::new(&[]); MAX_SLICES];
+        for (slice, chunk) in slices.iter_mut().zip(&self.chunks[..self.len]) {
+            *slice = IoSlice::new(match *chunk {
+                Chunk::Str(s) => s.as_bytes(),
+                Chunk::Scratch(start, end) => &self.scratch[start..end],
+            });
+        }
+        let mut slices = &mut slices[..self.len];
+        while !slices.is_empty() {
+            match self.w.write_vectored(slices) {
+                Ok(0) => return Err(io::ErrorKind::WriteZero.into()),
+                Ok(n) => IoSlice::advance_slices(&mut slices, n),
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+        self.len = 0;
+        self.scratch_len = 0;
+        Ok(())
+    }
+}
+
+/// Renders a leaf into the free part of the scratch buffer.
+///
+/// Once the scratch buffer is full, the pending chunks are written
+/// and the rest of the leaf is streamed to the writer.
+struct Spill<'s, 'a, 'w, W: Write> {
+    writer: &'s mut VectoredWriter<'a, 'w, W>,
+    /// The start of the leaf in the scratch buffer.
+    start: usize,
+    /// Whether the leaf is written to the writer directly.
+    streaming: bool,
+    error: Option<io::Error>,
+}
+
+impl<W: Write> core::fmt::Write for Spill<'_, '_, '_, W> {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        let writer = &mut *self.writer;
+        let result = if self.streaming {
+            writer.w.write_all(s.as_bytes())
+        } else {
+            let end = writer.scratch_len + s.len();
+            if end <= SCRATCH_LEN {
+                writer.scratch[writer.scratch_len..end].copy_from_slice(s.as_bytes());
+                writer.scratch_len = end;
+                return Ok(());
+            }
+            self.streaming = true;
+            writer.push_scratch(self.start);
+            writer
+                .flush()
+                .and_then(|()| writer.w.write_all(s.as_bytes()))
+        };
+        result.map_err(|e| {
+            self.error = Some(e);
+            core::fmt::Error
+        })
+    }
+}
+
+/// Write the rendered Twine to `w`.
+pub(crate) fn write_io<W: Write>(twine: &Twine<'_>, w: &mut W) -> io::Result<()> {
+    let mut writer = VectoredWriter::new(w);
+    match twine.try_for_each_leaf(Twine::MAX_DEPTH, WriteIoError::Depth, |child| {
+        writer.push(child).map_err(WriteIoError::Io)
+    }) {
+        Ok(()) => Ok(writer.flush()?),
+        Err(WriteIoError::Io(e)) => Err(e),
+        Err(WriteIoError::Depth) => Err(io::Error::other(
+            "Twine is nested deeper than Twine::MAX_DEPTH",
+        )),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::vec::Vec;
+
+    /// Accepts at most 3 bytes per call and is interrupted on every other call
+    struct Trickle {
+        bytes: Vec<u8>,
+        calls: usize,
+    }
+
+    impl Write for Trickle {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.calls += 1;
+            if self.calls.is_multiple_of(2) {
+                return Err(io::ErrorKind::Interrupted.into());
+            }
+            let n = buf.len().min(3);
+            self.bytes.extend_from_slice(&buf[..n]);
+            Ok(n)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    /// Fails on every write
+    struct Broken;
+
+    impl Write for Broken {
+        fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+            Err(io::Error::new(io::ErrorKind::BrokenPipe, "broken"))
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn write_io_partial_writes() {
+        let bump = bumpalo::Bump::new();
+        let number = 1234u32;
+        let args = format_args!("<{}>", 5);
+        let mut t: &Twine = bump.alloc(Twine::from("start-"));
+        for i in 0..100 {
+            let piece = match i % 4 {
+                0 => Twine::from("str-"),
+                1 => Twine::from(&number),
+                2 => Twine::from(&'ä'),
+                _ => Twine::from(&args),
+            };
+            t = bump.alloc(t + &*bump.alloc(piece));
+        }
+        let mut w = Trickle {
+            bytes: Vec::new(),
+            calls: 0,
+        };
+        t.write_io(&mut w).unwrap();
+        assert_eq!(std::str::from_utf8(&w.bytes).unwrap(), std::format!("{t}"));
+    }
+
+    #[test]
+    fn write_io_large_leaf() {
+        let head = Twine::from("HEAD");
+        let large = "x".repeat(SCRATCH_LEN + 44);
+        let leaf = Twine::display(&large);
+        let t = head.concat(&leaf);
+        let mut w = Vec::new();
+        t.write_io(&mut w).unwrap();
+        assert_eq!(
+            std::str::from_utf8(&w).unwrap(),
+            std::format!("HEAD{large}")
+        );
+    }
+
+    #[test]
+    fn write_io_renders_once() {
+        use core::cell::Cell;
+        let calls = Cell::new(0);
+        let render = |w: &mut dyn core::fmt::Write| {
+            calls.set(calls.get() + 1);
+            (0..50).try_for_each(|_| w.write_str("0123456789"))
+        };
+        let n = 42u32;
+        let lazy = Twine::from_fn(&render);
+        crate::twine!(let t = "head", n, lazy, lazy);
+        let expected = std::format!("{t}");
+        calls.set(0);
+        let mut w = Vec::new();
+        t.write_io(&mut w).unwrap();
+        assert_eq!(std::str::from_utf8(&w).unwrap(), expected);
+        assert_eq!(calls.get(), 2);
+    }
+
+    #[test]
+    fn write_io_error() {
+        let t = Twine::from(("foo", "bar"));
+        let err = t.write_io(&mut Broken).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::BrokenPipe);
+        assert_eq!(std::format!("{err}"), "broken");
+    }
+}
@@ -0,0 +1,1216 @@
+//! Lazy iteration over the rendered bytes, chars and chunks of a Twine.
+//!
+//! The leaves of the Twine are visited one at a time from either end with an explicit stack.
+//! Joins, paddings and truncations are taken apart into their Twines, items and fill chars,
+//! so that only their leaves are rendered.
+//! Str leaves are iterated in place,
+//! all other leaves are rendered into a small window, a part at a time.
+
+use core::fmt::Write;
+use core::iter::FusedIterator;
+
+use crate::join::JoinRef;
+#[cfg(feature = "alloc")]
+use crate::owned::BufLeaf;
+#[cfg(feature = "alloc")]
+use crate::TwineBuf;
+use crate::{Twine, TwineChild, TwineKind};
+
+/// Size of the window that non-str leaves are rendered into.
+const WINDOW: usize = 64;
+
+/// The stack entries left for the Twines nested in a leaf.
+const NESTED_DEPTH: usize = crate::nested_depth(Twine::MAX_DEPTH);
+
+/// A leaf of a Twine, visited by `Leaves`.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Leaf<'a> {
+    /// A leaf child and the number of bytes of it that are visited at most.
+    /// Strs are cut off in place instead.
+    Child(TwineChild<'a>, usize),
+    /// The item of a join at an index and the number of bytes of it that are visited at most.
+    Item(JoinRef<'a>, usize, usize),
+    /// The fill char of a padding and the number of times it is repeated.
+    Fill(char, usize),
+}
+
+impl<'a> Leaf<'a> {
+    /// The number of bytes of the rendered leaf that are visited at most.
+    fn max(&self) -> usize {
+        match *self {
+            Leaf::Child(_, max) | Leaf::Item(_, _, max) => max,
+            Leaf::Fill(fill, count) => fill.len_utf8().saturating_mul(count),
+        }
+    }
+
+    /// The length of the visited part of the rendered leaf, which renders it if needed.
+    fn len(&self) -> usize {
+        match *self {
+            Leaf::Child(TwineChild::Str(s), _) => s.len(),
+            Leaf::Child(child, usize::MAX) => child.len(NESTED_DEPTH),
+            Leaf::Item(join, i, usize::MAX) => join.0.item_len(i, NESTED_DEPTH),
+            Leaf::Fill(..) => self.max(),
+            _ => {
+                let mut measure = Measure {
+                    len: 0,
+                    max: self.max(),
+                };
+                let _ = self.write_to(&mut measure);
+                measure.len
+            }
+        }
+    }
+
+    /// The leaf cut off after at most `n` bytes, without splitting a char.
+    fn cut(self, n: usize) -> Self {
+        match self {
+            Leaf::Child(TwineChild::Str(s), max) => {
+                let mut end = n.min(s.len());
+                while !s.is_char_boundary(end) {
+                    end -= 1;
+                }
+                Leaf::Child(TwineChild::Str(&s[..end]), max)
+            }
+            Leaf::Child(child, max) => Leaf::Child(child, max.min(n)),
+            Leaf::Item(join, i, max) => Leaf::Item(join, i, max.min(n)),
+            Leaf::Fill(fill, count) => Leaf::Fill(fill, count.min(n / fill.len_utf8())),
+        }
+    }
+
+    /// Render the whole leaf, regardless of the number of bytes that are visited.
+    fn write_to(&self, mut w: &mut dyn Write) -> core::fmt::Result {
+        match *self {
+            Leaf::Child(child, _) => child.write_to(&mut w, NESTED_DEPTH),
+            Leaf::Item(join, i, _) => join.0.write_item(i, w, NESTED_DEPTH),
+            Leaf::Fill(fill, count) => (0..count).try_for_each(|_| w.write_char(fill)),
+        }
+    }
+}
+
+/// Counts the whole chars of the rendered text up to `max` bytes.
+///
+/// Fails as soon as the rendered text reaches `max`, to stop rendering.
+struct Measure {
+    len: usize,
+    max: usize,
+}
+
+impl Write for Measure {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        if s.len() < self.max - self.len {
+            self.len += s.len();
+            return Ok(());
+        }
+        let mut end = self.max - self.len;
+        while !s.is_char_boundary(end) {
+            end -= 1;
+        }
+        self.len += end;
+        Err(core::fmt::Error)
+    }
+}
+
+/// A pending part of a Twine while its leaves are iterated.
+#[derive(Debug, Clone, Copy)]
+enum Part<'a> {
+    /// A nested Twine.
+    Node(&'a Twine<'a>),
+    /// A child of a Twine that is not yet expanded.
+    Child(TwineChild<'a>),
+    /// The top of a spine and the number of spine nodes whose other children are still pending,
+    /// to be visited from the deepest node up.
+    Spine(&'a Twine<'a>, usize),
+    /// The remaining Twines of a slice, never empty.
+    Slice(&'a [Twine<'a>]),
+    /// The remaining strs of a concatenation, never empty.
+    Strs(&'a [&'a str]),
+    /// A Twine and the number of times it still has to be visited, at least 1.
+    Repeat(&'a Twine<'a>, usize),
+    /// The items of a join from an index on, or from the back the items before it,
+    /// with the separators between them, and the number of all items.
+    Join(JoinRef<'a>, usize, usize),
+    /// The fill char of a padding and the number of times it is repeated.
+    Fill(char, usize),
+    /// A Twine of which only the given number of bytes are visited,
+    /// or from the back, of which the given number of bytes at its end are skipped.
+    Clip(&'a Twine<'a>, usize),
+    /// The number of bytes of the parts above that are still visited,
+    /// or from the back, that are still skipped.
+    Limit(usize),
+    /// The remaining range of leaves of an owned Twine, never empty.
+    #[cfg(feature = "alloc")]
+    Buf(&'a TwineBuf, usize, usize),
+    /// A range of leaves of an owned Twine and the number of times it still has to be visited, at least 1.
+    #[cfg(feature = "alloc")]
+    BufRepeat(&'a TwineBuf, usize, usize, usize),
+}
+
+/// Visits the leaves of a Twine one at a time,
+/// from the front or from the back.
+///
+/// Unlike `Twine::try_for_each_leaf`, the traversal can be paused after every leaf.
+/// It ends early if the Twine is nested deeper than `Twine::MAX_DEPTH`
+/// or if a padding or truncation can not be measured.
+#[derive(Debug, Clone)]
+pub(crate) struct Leaves<'a> {
+    stack: [Option<Part<'a>>; Twine::MAX_DEPTH],
+    top: usize,
+    rev: bool,
+    overflow: bool,
+    /// The number of `Part::Limit` entries on the stack that still limit the leaves above them.
+    limits: usize,
+}
+
+impl<'a> Leaves<'a> {
+    /// Visit the leaves of the Twine from the front, or from the back if `rev` is set.
+    pub(crate) fn new(t: &Twine<'a>, rev: bool) -> Self {
+        let mut leaves = Leaves {
+            stack: [None; Twine::MAX_DEPTH],
+            top: 0,
+            rev,
+            overflow: false,
+            limits: 0,
+        };
+        match t.0 {
+            TwineKind::Null | TwineKind::Empty => {}
+            TwineKind::Unary(child) => leaves.push(Part::Child(child)),
+            TwineKind::Binary(l_child, r_child) => leaves.push_pair(l_child, r_child),
+        }
+        leaves
+    }
+
+    /// End the traversal early.
+    fn stop(&mut self) {
+        self.overflow = true;
+        self.top = 0;
+        self.limits = 0;
+    }
+
+    /// Push a part, or end the traversal if the stack is full.
+    #[inline]
+    fn push(&mut self, part: Part<'a>) {
+        if self.overflow {
+            return;
+        }
+        if self.top == Twine::MAX_DEPTH {
+            self.stop();
+            return;
+        }
+        self.stack[self.top] = Some(part);
+        self.top += 1;
+    }
+
+    /// Push both children of a node, so that the one visited first is on top.
+    #[inline]
+    fn push_pair(&mut self, l_child: TwineChild<'a>, r_child: TwineChild<'a>) {
+        self.push_seq([Part::Child(l_child), Part::Child(r_child)]);
+    }
+
+    /// Push parts given in rendering order, so that the one visited first is on top.
+    #[inline]
+    fn push_seq<const N: usize>(&mut self, parts: [Part<'a>; N]) {
+        if self.rev {
+            parts.into_iter().for_each(|part| self.push(part));
+        } else {
+            parts.into_iter().rev().for_each(|part| self.push(part));
+        }
+    }
+
+    /// Walk one step down the spine in the direction of the traversal:
+    /// the left spine from the front, the right spine from the back.
+    ///
+    /// Returns the nested Twine and the other child of this node, if there is one.
+    #[inline]
+    fn step(&self, t: &'a Twine<'a>) -> Option<(&'a Twine<'a>, Option<TwineChild<'a>>)> {
+        match (t.0, self.rev) {
+            (TwineKind::Unary(TwineChild::Twine(t)), _) => Some((t, None)),
+            (TwineKind::Binary(TwineChild::Twine(t), r_child), false) => Some((t, Some(r_child))),
+            (TwineKind::Binary(l_child, TwineChild::Twine(t)), true) => Some((t, Some(l_child))),
+            _ => None,
+        }
+    }
+
+    /// Walk `n` steps down the spine in the direction of the traversal.
+    #[inline]
+    fn nth_step(&self, t: &'a Twine<'a>, n: usize) -> &'a Twine<'a> {
+        let mut t = t;
+        for _ in 0..n {
+            match self.step(t) {
+                Some((next, _)) => t = next,
+                None => break,
+            }
+        }
+        t
+    }
+
+    /// Expand a join into its prefix, items and suffix.
+    fn push_join(&mut self, join: JoinRef<'a>) {
+        let count = join.0.count();
+        let start = if self.rev { count } else { 0 };
+        self.push_seq([
+            Part::Node(join.0.prefix()),
+            Part::Join(join, start, count),
+            Part::Node(join.0.suffix()),
+        ]);
+    }
+
+    /// Apply the limits of the truncations the leaf is nested in,
+    /// returning the part of it that is visited, if any.
+    fn limit(&mut self, leaf: Leaf<'a>) -> Option<Leaf<'a>> {
+        if self.limits == 0 {
+            return Some(leaf);
+        }
+        let limits = self.stack[..self.top]
+            .iter_mut()
+            .filter_map(|part| match part {
+                Some(Part::Limit(n)) => Some(n),
+                _ => None,
+            });
+        if self.rev {
+            // skip the end of the leaf, the innermost truncation first
+            let mut keep = leaf.len();
+            for n in limits.rev() {
+                if *n > 0 {
+                    let skip = keep.min(*n);
+                    *n -= skip;
+                    keep -= skip;
+                    if *n == 0 {
+                        self.limits -= 1;
+                    }
+                }
+            }
+            return (keep > 0).then(|| leaf.cut(keep));
+        }
+        let bound = limits.map(|n| *n).min().unwrap_or(usize::MAX);
+        let leaf = leaf.cut(bound);
+        let len = leaf.len();
+        let mut exhausted = None;
+        for (i, part) in self.stack[..self.top].iter_mut().enumerate() {
+            if let Some(Part::Limit(n)) = part {
+                *n -= len;
+                if *n == 0 && exhausted.is_none() {
+                    exhausted = Some(i);
+                }
+            }
+        }
+        if let Some(i) = exhausted {
+            // drop the rest of the outermost truncation that is done
+            self.stack[i..self.top].fill(None);
+            self.top = i;
+            self.limits = self.stack[..i]
+                .iter()
+                .filter(|part| matches!(part, Some(Part::Limit(_))))
+                .count();
+        }
+        Some(leaf)
+    }
+}
+
+impl<'a> Iterator for Leaves<'a> {
+    type Item = Leaf<'a>;
+
+    fn next(&mut self) -> Option<Leaf<'a>> {
+        /// Spines longer than this are split in half instead of walked once per child.
+        const SPLIT_LEN: usize = 8;
+
+        while self.top > 0 {
+            self.top -= 1;
+            let part = self.stack[self.top]
+                .take()
+                .expect("occupied stack entries are always Some");
+            let leaf = match part {
+                Part::Node(node) => {
+                    let mut len = 0;
+                    let mut bottom = node;
+                    while let Some((t, _)) = self.step(bottom) {
+                        bottom = t;
+                        len += 1;
+                    }
+                    if len > 0 {
+                        self.push(Part::Spine(node, len));
+                    }
+                    match bottom.0 {
+                        TwineKind::Null | TwineKind::Empty => {}
+                        TwineKind::Unary(child) => self.push(Part::Child(child)),
+                        TwineKind::Binary(l_child, r_child) => self.push_pair(l_child, r_child),
+                    }
+                    continue;
+                }
+                Part::Spine(spine, len) if len > SPLIT_LEN => {
+                    // visit the deeper half first and keep the upper half for later
+                    let mid = len / 2;
+                    self.push(Part::Spine(spine, mid));
+                    self.push(Part::Spine(self.nth_step(spine, mid), len - mid));
+                    continue;
+                }
+                Part::Spine(spine, len) => {
+                    if len > 1 {
+                        self.push(Part::Spine(spine, len - 1));
+                    }
+                    if let Some((_, Some(child))) = self.step(self.nth_step(spine, len - 1)) {
+                        self.push(Part::Child(child));
+                    }
+                    continue;
+                }
+                Part::Child(child) => match child {
+                    TwineChild::Twine(t) => {
+                        self.push(Part::Node(t));
+                        continue;
+                    }
+                    TwineChild::Slice([]) | TwineChild::Strs([]) | TwineChild::Repeat(_, 0) => {
+                        continue;
+                    }
+                    TwineChild::Slice(ts) => {
+                        self.push(Part::Slice(ts));
+                        continue;
+                    }
+                    TwineChild::Strs(strs) => {
+                        self.push(Part::Strs(strs));
+                        continue;
+                    }
+                    TwineChild::Repeat(t, count) => {
+                        self.push(Part::Repeat(t, count));
+                        continue;
+                    }
+                    TwineChild::Join(join) => {
+                        self.push_join(join);
+                        continue;
+                    }
+                    TwineChild::Pad(pad) => {
+                        match pad.split(NESTED_DEPTH) {
+                            Ok((fill, left, child, right)) => self.push_seq([
+                                Part::Fill(fill, left),
+                                Part::Node(child),
+                                Part::Fill(fill, right),
+                            ]),
+                            Err(_) => self.stop(),
+                        }
+                        continue;
+                    }
+                    TwineChild::Truncate(truncate) => {
+                        match truncate.split(NESTED_DEPTH) {
+                            Ok((child, _, None)) => self.push(Part::Node(child)),
+                            Ok((child, ellipsis, Some(kept))) => {
+                                let clip = if self.rev {
+                                    child.len_within(NESTED_DEPTH).saturating_sub(kept)
+                                } else {
+                                    kept
+                                };
+                                self.push_seq([Part::Clip(child, clip), Part::Node(ellipsis)]);
+                            }
+                            Err(_) => self.stop(),
+                        }
+                        continue;
+                    }
+                    #[cfg(feature = "alloc")]
+                    TwineChild::Buf(buf) => {
+                        if buf.leaf_count() > 0 {
+                            self.push(Part::Buf(buf, 0, buf.leaf_count()));
+                        }
+                        continue;
+                    }
+                    leaf => Leaf::Child(leaf, usize::MAX),
+                },
+                Part::Slice(ts) => {
+                    let split = if self.rev {
+                        ts.split_last()
+                    } else {
+                        ts.split_first()
+                    };
+                    let (t, rest) = split.expect("pending slices are never empty");
+                    if !rest.is_empty() {
+                        self.push(Part::Slice(rest));
+                    }
+                    self.push(Part::Node(t));
+                    continue;
+                }
+                Part::Strs(strs) => {
+                    let split = if self.rev {
+                        strs.split_last()
+                    } else {
+                        strs.split_first()
+                    };
+                    let (s, rest) = split.expect("pending strs are never empty");
+                    if !rest.is_empty() {
+                        self.push(Part::Strs(rest));
+                    }
+                    Leaf::Child(TwineChild::Str(s), usize::MAX)
+                }
+                Part::Repeat(t, count) => {
+                    if count > 1 {
+                        self.push(Part::Repeat(t, count - 1));
+                    }
+                    self.push(Part::Node(t));
+                    continue;
+                }
+                Part::Join(join, i, count) => {
+                    // the item at `i`, or from the back the one before it, followed by the rest
+                    let (item, rest) = match self.rev {
+                        false if i < count => (i, i + 1),
+                        true if i > 0 => (i - 1, i - 1),
+                        _ => continue,
+                    };
+                    let separator = if self.rev { item } else { rest };
+                    if separator > 0 && separator < count {
+                        self.push(Part::Join(join, rest, count));
+                        self.push(Part::Node(join.0.separator(separator, count)));
+                    }
+                    Leaf::Item(join, item, usize::MAX)
+                }
+                Part::Fill(_, 0) => continue,
+                Part::Fill(fill, count) => Leaf::Fill(fill, count),
+                Part::Clip(t, 0) => {
+                    // from the front nothing of it is visited, from the back nothing is skipped
+                    if self.rev {
+                        self.push(Part::Node(t));
+                    }
+                    continue;
+                }
+                Part::Clip(t, n) => {
+                    self.push(Part::Limit(n));
+                    self.push(Part::Node(t));
+                    if !self.overflow {
+                        self.limits += 1;
+                    }
+                    continue;
+                }
+                Part::Limit(n) => {
+                    if n > 0 {
+                        self.limits -= 1;
+                    }
+                    continue;
+                }
+                #[cfg(feature = "alloc")]
+                Part::Buf(buf, start, end) => {
+                    let (i, start, end) = if self.rev {
+                        (end - 1, start, end - 1)
+                    } else {
+                        (start, start + 1, end)
+                    };
+                    if start < end {
+                        self.push(Part::Buf(buf, start, end));
+                    }
+                    match buf.leaf(i) {
+                        BufLeaf::Child(child) => self.push(Part::Child(child)),
+                        BufLeaf::Repeat(_, _, 0) => {}
+                        BufLeaf::Repeat(start, end, more) => {
+                            self.push(Part::BufRepeat(buf, start, end, more));
+                        }
+                    }
+                    continue;
+                }
+                #[cfg(feature = "alloc")]
+                Part::BufRepeat(buf, start, end, count) => {
+                    if count > 1 {
+                        self.push(Part::BufRepeat(buf, start, end, count - 1));
+                    }
+                    self.push(Part::Buf(buf, start, end));
+                    continue;
+                }
+            };
+            if let Some(leaf) = self.limit(leaf) {
+                return Some(leaf);
+            }
+        }
+        None
+    }
+}
+
+impl FusedIterator for Leaves<'_> {}
+
+/// Captures the whole chars of the rendered text in the byte range `from..to`.
+///
+/// Fails as soon as the rendered text reaches `to`, to stop rendering.
+struct Capture<'w> {
+    window: &'w mut [u8; WINDOW],
+    from: usize,
+    to: usize,
+    /// The number of bytes rendered so far.
+    pos: usize,
+    /// The position of the first captured byte.
+    first: usize,
+    len: usize,
+}
+
+impl Write for Capture<'_> {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        let start = self.pos;
+        self.pos += s.len();
+        if self.pos > self.from {
+            let mut lo = self.from.saturating_sub(start);
+            while !s.is_char_boundary(lo) {
+                lo += 1;
+            }
+            // leaves may ignore the error and keep on writing
+            let mut hi = self.to.saturating_sub(start).min(s.len());
+            while !s.is_char_boundary(hi) {
+                hi -= 1;
+            }
+            if lo < hi {
+                if self.len == 0 {
+                    self.first = start + lo;
+                }
+                self.window[self.len..self.len + hi - lo].copy_from_slice(&s.as_bytes()[lo..hi]);
+                self.len += hi - lo;
+            }
+        }
+        if self.pos >= self.to {
+            Err(core::fmt::Error)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Render the whole chars of the leaf in the byte range `from..to` into the window.
+///
+/// Returns the position of the first rendered byte, the number of rendered bytes
+/// and whether the visited part of the leaf ends before `to`.
+fn render_window(
+    leaf: &Leaf<'_>,
+    window: &mut [u8; WINDOW],
+    from: usize,
+    to: usize,
+) -> (usize, usize, bool) {
+    let max = leaf.max();
+    let to = to.min(max);
+    if from >= to {
+        return (from, 0, true);
+    }
+    let mut capture = Capture {
+        window,
+        from,
+        to,
+        pos: 0,
+        first: from,
+        len: 0,
+    };
+    // fails when the window is full
+    let _ = match *leaf {
+        Leaf::Fill(fill, count) => {
+            // skip the fill chars in front of the window
+            let width = fill.len_utf8();
+            let skip = from.div_ceil(width).min(count);
+            capture.pos = skip * width;
+            (skip..count).try_for_each(|_| capture.write_char(fill))
+        }
+        _ => leaf.write_to(&mut capture),
+    };
+    (capture.first, capture.len, capture.pos < to || to == max)
+}
+
+/// The rendered text at one end of a Twine, a leaf at a time.
+#[derive(Debug, Clone)]
+struct End<'a> {
+    leaves: Leaves<'a>,
+    /// The not yet iterated bytes of the current str leaf.
+    bytes: &'a [u8],
+    /// The current rendered leaf and the position in its rendered text
+    /// up to which it is rendered into the window, or from the back, from which it is.
+    leaf: Option<(Leaf<'a>, usize)>,
+    window: [u8; WINDOW],
+    /// The not yet iterated range of the window.
+    start: usize,
+    end: usize,
+}
+
+impl<'a> End<'a> {
+    fn new(t: &Twine<'a>, rev: bool) -> Self {
+        End {
+            leaves: Leaves::new(t, rev),
+            bytes: &[],
+            leaf: None,
+            window: [0; WINDOW],
+            start: 0,
+            end: 0,
+        }
+    }
+
+    /// The not yet iterated bytes of the current leaf, never empty.
+    ///
+    /// Renders the next part of the current leaf or moves on to the next leaf if needed.
+    fn peek(&mut self) -> Option<&[u8]> {
+        loop {
+            if !self.bytes.is_empty() {
+                return Some(self.bytes);
+            }
+            if self.start < self.end {
+                return Some(&self.window[self.start..self.end]);
+            }
+            if let Some((leaf, pos)) = self.leaf {
+                let (from, to) = if self.leaves.rev {
+                    (pos.saturating_sub(WINDOW), pos)
+                } else {
+                    (pos, pos.saturating_add(WINDOW))
+                };
+                let (first, len, complete) = render_window(&leaf, &mut self.window, from, to);
+                self.start = 0;
+                self.end = len;
+                self.leaf = if len == 0 {
+                    None
+                } else if self.leaves.rev {
+                    (first > 0).then_some((leaf, first))
+                } else {
+                    (!complete).then_some((leaf, first + len))
+                };
+                continue;
+            }
+            match self.leaves.next()? {
+                Leaf::Child(TwineChild::Str(s), _) => self.bytes = s.as_bytes(),
+                leaf => {
+                    let pos = if self.leaves.rev { leaf.len() } else { 0 };
+                    self.leaf = Some((leaf, pos));
+                }
+            }
+        }
+    }
+
+    /// Mark `n` bytes returned by `peek` as iterated.
+    fn consume(&mut self, n: usize) {
+        match (self.bytes.is_empty(), self.leaves.rev) {
+            (false, false) => self.bytes = &self.bytes[n..],
+            (false, true) => self.bytes = &self.bytes[..self.bytes.len() - n],
+            (true, false) => self.start += n,
+            (true, true) => self.end -= n,
+        }
+    }
+}
+
+/// The rendered text of a Twine, iterated from both ends.
+#[derive(Debug, Clone)]
+struct Text<'a> {
+    twine: Twine<'a>,
+    front: End<'a>,
+    /// Only set up when the text is iterated from the back.
+    back: Option<End<'a>>,
+    /// The number of bytes iterated from the front.
+    consumed: usize,
+    /// The number of bytes not yet iterated, known once the text is iterated from the back.
+    remaining: Option<usize>,
+}
+
+impl<'a> Text<'a> {
+    fn new(twine: Twine<'a>) -> Self {
+        Text {
+            twine,
+            front: End::new(&twine, false),
+            back: None,
+            consumed: 0,
+            remaining: None,
+        }
+    }
+
+    /// The next bytes at the front, never empty.
+    fn front(&mut self) -> Option<&[u8]> {
+        if self.remaining == Some(0) {
+            return None;
+        }
+        let bytes = self.front.peek()?;
+        Some(match self.remaining {
+            Some(remaining) => &bytes[..remaining.min(bytes.len())],
+            None => bytes,
+        })
+    }
+
+    /// The next bytes at the back, never empty.
+    fn back(&mut self) -> Option<&[u8]> {
+        if self.back.is_none() {
+            self.remaining = Some(self.twine.len().saturating_sub(self.consumed));
+            self.back = Some(End::new(&self.twine, true));
+        }
+        let remaining = self.remaining?;
+        if remaining == 0 {
+            return None;
+        }
+        let bytes = self.back.as_mut()?.peek()?;
+        Some(&bytes[bytes.len() - remaining.min(bytes.len())..])
+    }
+
+    fn consume_front(&mut self, n: usize) {
+        self.front.consume(n);
+        self.consumed += n;
+        if let Some(remaining) = &mut self.remaining {
+            *remaining -= n;
+        }
+    }
+
+    fn consume_back(&mut self, n: usize) {
+        if let Some(back) = &mut self.back {
+            back.consume(n);
+        }
+        if let Some(remaining) = &mut self.remaining {
+            *remaining -= n;
+        }
+    }
+
+    /// The bounds of the number of bytes not yet iterated.
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.remaining {
+            Some(remaining) => (remaining, Some(remaining)),
+            None => (0, None),
+        }
+    }
+}
+
+/// The number of bytes of the UTF-8 encoded char starting with the byte.
+#[inline]
+fn utf8_width(first: u8) -> usize {
+    match first {
+        0x00..=0x7f => 1,
+        0xc0..=0xdf => 2,
+        0xe0..=0xef => 3,
+        _ => 4,
+    }
+}
+
+/// The char encoded by the bytes.
+#[inline]
+fn decode(bytes: &[u8]) -> char {
+    core::str::from_utf8(bytes)
+        .ok()
+        .and_then(|s| s.chars().next())
+        .expect("rendered Twines are valid UTF-8")
+}
+
+/// An iterator over the chars of a rendered Twine, created by `Twine::chars`.
+///
+/// It keeps an explicit stack of `Twine::MAX_DEPTH` entries for each end of the Twine,
+/// which makes it about 5.5 KiB large on 64-bit targets.
+/// Keep it in place while iterating it rather than moving it around or storing it.
+#[derive(Debug, Clone)]
+pub struct Chars<'a>(Text<'a>);
+
+impl Iterator for Chars<'_> {
+    type Item = char;
+
+    fn next(&mut self) -> Option<char> {
+        let bytes = self.0.front()?;
+        let width = utf8_width(bytes[0]);
+        let ch = decode(&bytes[..width]);
+        self.0.consume_front(width);
+        Some(ch)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (min, max) = self.0.size_hint();
+        (min.div_ceil(4), max)
+    }
+}
+
+impl DoubleEndedIterator for Chars<'_> {
+    fn next_back(&mut self) -> Option<char> {
+        let bytes = self.0.back()?;
+        let start = bytes
+            .iter()
+            .rposition(|b| b & 0xc0 != 0x80)
+            .expect("rendered Twines are valid UTF-8");
+        let width = bytes.len() - start;
+        let ch = decode(&bytes[start..]);
+        self.0.consume_back(width);
+        Some(ch)
+    }
+}
+
+impl FusedIterator for Chars<'_> {}
+
+/// An iterator over the bytes of a rendered Twine, created by `Twine::bytes`.
+///
+/// As large as `Chars`.
+#[derive(Debug, Clone)]
+pub struct Bytes<'a>(Text<'a>);
+
+impl Iterator for Bytes<'_> {
+    type Item = u8;
+
+    fn next(&mut self) -> Option<u8> {
+        let byte = self.0.front()?[0];
+        self.0.consume_front(1);
+        Some(byte)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl DoubleEndedIterator for Bytes<'_> {
+    fn next_back(&mut self) -> Option<u8> {
+        let bytes = self.0.back()?;
+        let byte = bytes[bytes.len() - 1];
+        self.0.consume_back(1);
+        Some(byte)
+    }
+}
+
+impl FusedIterator for Bytes<'_> {}
+
+/// A piece of a rendered Twine, returned by `Chunks::next`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Chunk<'a, 'c> {
+    /// A str leaf of the Twine, which can be kept as long as the Twine.
+    Borrowed(&'a str),
+    /// A part of a rendered leaf, which is only valid until the next chunk is requested.
+    Rendered(&'c str),
+}
+
+impl<'a: 'c, 'c> Chunk<'a, 'c> {
+    /// The text of the chunk.
+    #[inline(always)]
+    #[must_use]
+    pub fn as_str(&self) -> &'c str {
+        match *self {
+            Chunk::Borrowed(s) | Chunk::Rendered(s) => s,
+        }
+    }
+}
+
+/// The non-empty pieces of a rendered Twine in order, created by `Twine::chunks`.
+///
+/// Str leaves are returned as they are,
+/// all other leaves are rendered into a small internal buffer, a part at a time.
+/// Joins, paddings and truncations are taken apart, so only their leaves are rendered.
+/// Every part of a leaf is rendered from the start of the leaf,
+/// so a leaf longer than the buffer, like a long Display value, closure or `fmt::Arguments`,
+/// is rendered once for each of its parts.
+/// As rendered chunks borrow that buffer, `Chunks` is not an `Iterator`,
+/// use `while let Some(chunk) = chunks.next()` instead.
+#[derive(Debug, Clone)]
+pub struct Chunks<'a> {
+    leaves: Leaves<'a>,
+    /// The current rendered leaf and the position in its rendered text up to which it is rendered.
+    leaf: Option<(Leaf<'a>, usize)>,
+    window: [u8; WINDOW],
+}
+
+impl<'a> Chunks<'a> {
+    /// The next chunk, or `None` if the whole Twine has been returned.
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Option<Chunk<'a, '_>> {
+        loop {
+            if let Some((leaf, pos)) = self.leaf {
+                let (first, len, complete) =
+                    render_window(&leaf, &mut self.window, pos, pos.saturating_add(WINDOW));
+                self.leaf = (!complete && len > 0).then_some((leaf, first + len));
+                if len > 0 {
+                    let s = core::str::from_utf8(&self.window[..len])
+                        .expect("rendered Twines are valid UTF-8");
+                    return Some(Chunk::Rendered(s));
+                }
+                continue;
+            }
+            match self.leaves.next()? {
+                Leaf::Child(TwineChild::Str(""), _) => {}
+                Leaf::Child(TwineChild::Str(s), _) => return Some(Chunk::Borrowed(s)),
+                leaf => self.leaf = Some((leaf, 0)),
+            }
+        }
+    }
+}
+
+impl<'a> Twine<'a> {
+    /// The non-empty pieces of the rendered Twine in order.
+    ///
+    /// Strs are returned without copying them, e.g. to pass them to vectored writes,
+    /// and all other leaves are rendered on the fly into a small buffer, see `Chunks`.
+    /// The items of a join are looked up by iterating its items up to them,
+    /// and paddings and truncations measure their content first.
+    ///
+    /// If the Twine is nested deeper than `Twine::MAX_DEPTH`,
+    /// only the part that could be visited is returned.
+    ///
+    /// # Example
+    /// ```
+    /// # use twine::{Chunk, Twine};
+    /// let id = 42u32;
+    /// let (name, id) = (Twine::from("id="), Twine::from(&id));
+    /// let t = name.concat(&id);
+    /// let mut chunks = t.chunks();
+    /// assert_eq!(chunks.next(), Some(Chunk::Borrowed("id=")));
+    /// assert_eq!(chunks.next(), Some(Chunk::Rendered("42")));
+    /// assert_eq!(chunks.next(), None);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn chunks(&self) -> Chunks<'a> {
+        Chunks {
+            leaves: Leaves::new(self, false),
+            leaf: None,
+            window: [0; WINDOW],
+        }
+    }
+
+    /// Call `f` with all non-empty pieces of the rendered Twine in order.
+    ///
+    /// Strs are passed without copying them,
+    /// all other leaves are passed in the pieces they render themselves in,
+    /// e.g. to feed the Twine into a hasher without rendering it into a String first.
+    ///
+    /// # Errors
+    ///
+    /// This method returns an `fmt::Error` Error Result if the Twine is nested deeper than `Twine::MAX_DEPTH`
+    /// or if rendering a leaf fails.
+    ///
+    /// # Example
+    /// ```
+    /// # use twine::Twine;
+    /// let n = 7u8;
+    /// let (name, n) = (Twine::from("n="), Twine::from(&n));
+    /// let t = name.concat(&n);
+    /// let mut chunks = Vec::new();
+    /// t.for_each_chunk(|chunk| chunks.push(chunk.to_string())).unwrap();
+    /// assert_eq!(chunks, ["n=", "7"]);
+    /// ```
+    pub fn for_each_chunk(&self, f: impl FnMut(&str)) -> core::fmt::Result {
+        /// Passes every non-empty str written to it to the closure
+        struct ChunkWriter<F>(F);
+        impl<F: FnMut(&str)> Write for ChunkWriter<F> {
+            fn write_str(&mut self, s: &str) -> core::fmt::Result {
+                if !s.is_empty() {
+                    (self.0)(s);
+                }
+                Ok(())
+            }
+        }
+        self.write_to(&mut ChunkWriter(f))
+    }
+
+    /// An iterator over the chars of the rendered Twine.
+    ///
+    /// The Twine is rendered lazily while it is iterated:
+    /// strs are iterated in place, joins, paddings and truncations are taken apart,
+    /// and all other leaves are rendered into a small internal buffer, a part at a time,
+    /// so nothing is allocated.
+    /// As for `chunks`, a leaf longer than the buffer is rendered from its start once for each of its parts.
+    /// Iterating from the back measures the whole Twine first, see `len`,
+    /// and measures every rendered leaf once more to find its end.
+    /// The iterator is about 5.5 KiB large, see `Chars`.
+    ///
+    /// If the Twine is nested deeper than `Twine::MAX_DEPTH`,
+    /// only the part that could be visited is iterated.
+    ///
+    /// # Example
+    /// ```
+    /// # use twine::Twine;
+    /// let n = 42u32;
+    /// let (name, n) = (Twine::from("ä="), Twine::from(&n));
+    /// let t = name.concat(&n);
+    /// assert!(t.chars().all(|c| c == 'ä' || c == '=' || c.is_ascii_digit()));
+    /// assert_eq!(t.chars().rev().collect::<String>(), "24=ä");
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn chars(&self) -> Chars<'a> {
+        Chars(Text::new(*self))
+    }
+
+    /// An iterator over the bytes of the rendered Twine.
+    ///
+    /// Rendered lazily like `chars`, at the same cost.
+    ///
+    /// # Example
+    /// ```
+    /// # use twine::Twine;
+    /// let n = 7u8;
+    /// let (name, n) = (Twine::from("n"), Twine::from(&n));
+    /// let t = name.concat(&n);
+    /// assert_eq!(t.bytes().collect::<Vec<u8>>(), b"n7");
+    /// assert_eq!(t.bytes().next_back(), Some(b'7'));
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn bytes(&self) -> Bytes<'a> {
+        Bytes(Text::new(*self))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::string::{String, ToString};
+    use std::vec::Vec;
+
+    /// Check all ways to iterate the Twine against its rendered String.
+    fn check(t: &Twine<'_>) {
+        let s = t.to_string();
+        assert_eq!(t.chars().collect::<String>(), s);
+        assert_eq!(
+            t.chars().rev().collect::<String>(),
+            s.chars().rev().collect::<String>()
+        );
+        assert_eq!(t.bytes().collect::<Vec<u8>>(), s.as_bytes());
+        assert_eq!(
+            t.bytes().rev().collect::<Vec<u8>>(),
+            s.bytes().rev().collect::<Vec<u8>>()
+        );
+        // meet in the middle from both ends
+        let mut chars = t.chars();
+        let mut expected = s.chars();
+        let mut i = 0usize;
+        loop {
+            let (a, b) = if i.is_multiple_of(3) {
+                (chars.next_back(), expected.next_back())
+            } else {
+                (chars.next(), expected.next())
+            };
+            assert_eq!(a, b);
+            if a.is_none() {
+                break;
+            }
+            i += 1;
+        }
+        assert_eq!(chars.next(), None);
+    }
+
+    #[test]
+    fn size_of() {
+        // an explicit stack of parts of 5 words each and a window for both ends
+        let size_of_stack = Twine::MAX_DEPTH * 5 * core::mem::size_of::<usize>();
+        let size_of_chars = core::mem::size_of::<Chars<'static>>();
+        assert!(size_of_chars > 2 * size_of_stack);
+        assert!(size_of_chars < 2 * (size_of_stack + WINDOW + 256));
+        assert_eq!(core::mem::size_of::<Bytes<'static>>(), size_of_chars);
+    }
+
+    #[test]
+    fn mixed_leaves() {
+        let word = "wörd";
+        let n = -1234i32;
+        let x = 0xabu8;
+        let f = 1.25f64;
+        let strs = ["a", "", "bc"];
+        let args = format_args!("<{}>", "äöü".repeat(30));
+        crate::twine!(
+            let t = word,
+            ' ',
+            n,
+            Twine::hex(&x),
+            f,
+            Twine::concat_strs(&strs),
+            args,
+            'ß',
+        );
+        check(&t);
+        let dash = Twine::from(&'—');
+        let rule = Twine::repeat(&t, 3);
+        let parts = [rule, dash, Twine::empty(), t];
+        let all = Twine::concat_all(&parts);
+        check(&all);
+        check(&Twine::null());
+        check(&Twine::empty());
+        #[cfg(feature = "alloc")]
+        check(&TwineBuf::from(&all).as_twine());
+    }
+
+    #[test]
+    fn chunks() {
+        let word = "wörd";
+        let n = 12345u32;
+        let long = format_args!("{}", "äöü".repeat(30));
+        crate::twine!(let t = word, "", n, long, ' ');
+        let mut chunks = t.chunks();
+        let mut joined = String::new();
+        let mut count = 0;
+        while let Some(chunk) = chunks.next() {
+            assert!(chunk.as_str().len() <= WINDOW || matches!(chunk, Chunk::Borrowed(_)));
+            assert!(!chunk.as_str().is_empty());
+            joined.push_str(chunk.as_str());
+            count += 1;
+        }
+        assert_eq!(joined, t.to_string());
+        // the str, the integer, 180 bytes in 3 parts of whole chars and the char
+        assert_eq!(count, 6);
+        assert_eq!(chunks.next(), None);
+
+        let mut joined = String::new();
+        t.for_each_chunk(|chunk| joined.push_str(chunk)).unwrap();
+        assert_eq!(joined, t.to_string());
+    }
+
+    #[test]
+    fn structured_leaves() {
+        let n = 1234567u32;
+        let word = "wörd";
+        crate::twine!(let t = word, '-', n);
+        let number = Twine::from(&n);
+        let items = [t, Twine::from("ä"), Twine::empty(), number];
+        let join = Twine::join(&items, ", ")
+            .last_separator(" & ")
+            .prefix("[")
+            .suffix("]");
+        let join = Twine::from(&join);
+        check(&join);
+        let pad = Twine::center(&join, 100, '·');
+        let pad = Twine::from(&pad);
+        check(&pad);
+        for max in [0, 1, 5, 40, 60, 120, 200, 250] {
+            let truncate = Twine::truncate(&pad, max, "…");
+            let truncate = Twine::from(&truncate);
+            check(&truncate);
+            let both = truncate.concat(&join);
+            let nested = Twine::truncate(&both, max / 2 + 3, "..").chars();
+            check(&Twine::from(&nested));
+        }
+        let empty: [u8; 0] = [];
+        let parens = Twine::join(&empty, ",").prefix("(").suffix(")");
+        check(&Twine::from(&parens));
+    }
+
+    #[test]
+    fn renders_items_once() {
+        use core::cell::Cell;
+        let calls = Cell::new(0);
+        let render = |w: &mut dyn Write| {
+            calls.set(calls.get() + 1);
+            w.write_str("ab")
+        };
+        let item = Twine::from_fn(&render);
+        let items = [item; 100];
+        let join = Twine::join(&items, ", ");
+        let t = Twine::from(&join);
+        let mut chunks = t.chunks();
+        let mut len = 0;
+        while let Some(chunk) = chunks.next() {
+            len += chunk.as_str().len();
+        }
+        assert_eq!(len, 398);
+        assert_eq!(calls.get(), 100);
+        calls.set(0);
+        assert_eq!(t.chars().count(), 398);
+        assert_eq!(calls.get(), 100);
+        calls.set(0);
+        // measured for the length of the Twine and for the end of each item
+        assert_eq!(t.bytes().rev().count(), 398);
+        assert_eq!(calls.get(), 300);
+    }
+
+    #[test]
+    fn deep_chains() {
+        const LEN: u32 = 10_000;
+        let bump = bumpalo::Bump::new();
+        let mut left: &Twine = bump.alloc(Twine::empty());
+        let mut right: &Twine = bump.alloc(Twine::empty());
+        for i in 0..LEN {
+            let digit: &Twine = bump.alloc(Twine::from(&*bump.alloc(i % 10)));
+            left = bump.alloc(left.concat(digit));
+            right = bump.alloc(digit.concat(right));
+        }
+        check(left);
+        check(right);
+        assert_eq!(left.chars().count(), LEN as usize);
+    }
+
+    #[test]
+    fn lazy() {
+        use core::cell::Cell;
+        let calls = Cell::new(0);
+        let render = |w: &mut dyn Write| {
+            calls.set(calls.get() + 1);
+            w.write_str("x")
+        };
+        let head = Twine::from("head");
+        let tail = Twine::from_fn(&render);
+        let t = head.concat(&tail);
+        assert!(t.chars().take(4).eq("head".chars()));
+        assert_eq!(calls.get(), 0);
+    }
+}
@@ -0,0 +1,279 @@
+//! Lazily joined items with separators, prefix and suffix.
+
+use crate::{Twine, TwineChild, TwineKind, TwinePiece};
+
+/// Items joined with separators, created by `Twine::join`.
+///
+/// Create a Twine of it with `Twine::from(&join)`, see [views](crate#views).
+/// The items are only iterated when the Twine is measured or rendered,
+/// so they are iterated once for every call to `len`, `estimated_capacity` or `write_to`.
+///
+/// # Example
+/// ```
+/// # use twine::Twine;
+/// let names = ["a", "b", "c"];
+/// let list = Twine::join(&names, ", ").last_separator(" and ");
+/// assert_eq!(Twine::from(&list).to_string(), "a, b and c");
+/// let squares = Twine::join((1..4u32).map(|i| i * i), ",").prefix("[").suffix("]");
+/// assert_eq!(Twine::from(&squares).to_string(), "[1,4,9]");
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Join<'a, I> {
+    items: I,
+    separator: Twine<'a>,
+    last_separator: Option<Twine<'a>>,
+    prefix: Twine<'a>,
+    suffix: Twine<'a>,
+}
+
+impl<'a> Twine<'a> {
+    /// Join all items with the separator.
+    ///
+    /// The items can be any clonable iterable of the values accepted by the `twine!` macro,
+    /// like a slice of strs, integers or Twines.
+    ///
+    /// # Example
+    /// ```
+    /// # use twine::Twine;
+    /// let numbers = vec![1, 2, 3];
+    /// let join = Twine::join(&numbers, " + ");
+    /// assert_eq!(Twine::from(&join).to_string(), "1 + 2 + 3");
+    /// let empty: [&str; 0] = [];
+    /// let join = Twine::join(&empty, ", ");
+    /// assert_eq!(Twine::from(&join).is_empty(), true);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn join<I>(items: I, separator: impl Into<Twine<'a>>) -> Join<'a, I>
+    where
+        I: IntoIterator + Clone,
+        I::Item: TwinePiece,
+    {
+        Join {
+            items,
+            separator: separator.into(),
+            last_separator: None,
+            prefix: Twine::empty(),
+            suffix: Twine::empty(),
+        }
+    }
+}
+
+impl<'a, I> Join<'a, I>
+where
+    I: IntoIterator + Clone,
+    I::Item: TwinePiece,
+{
+    /// Use a distinct separator between the last two items.
+    #[inline]
+    #[must_use]
+    pub fn last_separator(mut self, last_separator: impl Into<Twine<'a>>) -> Self {
+        self.last_separator = Some(last_separator.into());
+        self
+    }
+
+    /// Render the prefix in front of the items, even if there are none.
+    #[inline]
+    #[must_use]
+    pub fn prefix(mut self, prefix: impl Into<Twine<'a>>) -> Self {
+        self.prefix = prefix.into();
+        self
+    }
+
+    /// Render the suffix after the items, even if there are none.
+    #[inline]
+    #[must_use]
+    pub fn suffix(mut self, suffix: impl Into<Twine<'a>>) -> Self {
+        self.suffix = suffix.into();
+        self
+    }
+
+    /// Visit the prefix, the items with the separators between them and the suffix in rendering order.
+    fn try_for_each_part<E>(
+        &self,
+        mut f: impl FnMut(&Twine<'_>) -> Result<(), E>,
+    ) -> Result<(), E> {
+        f(&self.prefix)?;
+        let mut items = self.items.clone().into_iter().peekable();
+        let mut first = true;
+        while let Some(item) = items.next() {
+            if !first {
+                match self.last_separator {
+                    Some(last_separator) if items.peek().is_none() => f(&last_separator)?,
+                    _ => f(&self.separator)?,
+                }
+            }
+            f(&item.to_twine())?;
+            first = false;
+        }
+        f(&self.suffix)
+    }
+}
+
+/// Joins as a leaf child of a Twine, independent of the type of the items.
+///
+/// The parts are measured and rendered with at most `depth` stack entries each.
+pub(crate) trait Render {
+    fn is_empty(&self, depth: usize) -> bool;
+    fn len(&self, depth: usize) -> usize;
+    fn estimated_capacity(&self, depth: usize) -> usize;
+    fn write_to(&self, w: &mut dyn core::fmt::Write, depth: usize) -> core::fmt::Result;
+
+    /// The number of items, iterating them without rendering them.
+    fn count(&self) -> usize;
+    fn prefix(&self) -> &Twine<'_>;
+    fn suffix(&self) -> &Twine<'_>;
+    /// The separator in front of the item at index `i` of `count` items.
+    fn separator(&self, i: usize, count: usize) -> &Twine<'_>;
+    /// The length of the item at index `i`,
+    /// which is looked up by iterating the items up to it.
+    fn item_len(&self, i: usize, depth: usize) -> usize;
+    /// Render the item at index `i`, which is looked up by iterating the items up to it.
+    fn write_item(&self, i: usize, w: &mut dyn core::fmt::Write, depth: usize)
+        -> core::fmt::Result;
+}
+
+impl<I> Render for Join<'_, I>
+where
+    I: IntoIterator + Clone,
+    I::Item: TwinePiece,
+{
+    fn is_empty(&self, depth: usize) -> bool {
+        self.try_for_each_part(|t| {
+            if t.is_empty_within(depth) {
+                Ok(())
+            } else {
+                Err(())
+            }
+        })
+        .is_ok()
+    }
+
+    fn len(&self, depth: usize) -> usize {
+        let mut len = 0;
+        let _ = self.try_for_each_part(|t| {
+            len += t.len_within(depth);
+            Ok::<(), ()>(())
+        });
+        len
+    }
+
+    fn estimated_capacity(&self, depth: usize) -> usize {
+        let mut capacity = 0;
+        let _ = self.try_for_each_part(|t| {
+            capacity += t.estimated_capacity_within(depth);
+            Ok::<(), ()>(())
+        });
+        capacity
+    }
+
+    fn write_to(&self, mut w: &mut dyn core::fmt::Write, depth: usize) -> core::fmt::Result {
+        self.try_for_each_part(|t| t.write_to_within(&mut w, depth))
+    }
+
+    fn count(&self) -> usize {
+        self.items.clone().into_iter().count()
+    }
+
+    fn prefix(&self) -> &Twine<'_> {
+        &self.prefix
+    }
+
+    fn suffix(&self) -> &Twine<'_> {
+        &self.suffix
+    }
+
+    fn separator(&self, i: usize, count: usize) -> &Twine<'_> {
+        match &self.last_separator {
+            Some(last_separator) if i + 1 == count => last_separator,
+            _ => &self.separator,
+        }
+    }
+
+    fn item_len(&self, i: usize, depth: usize) -> usize {
+        let item = self.items.clone().into_iter().nth(i);
+        item.map_or(0, |item| item.to_twine().len_within(depth))
+    }
+
+    fn write_item(
+        &self,
+        i: usize,
+        mut w: &mut dyn core::fmt::Write,
+        depth: usize,
+    ) -> core::fmt::Result {
+        let item = self.items.clone().into_iter().nth(i);
+        item.map_or(Ok(()), |item| {
+            item.to_twine().write_to_within(&mut w, depth)
+        })
+    }
+}
+
+/// A reference to a join of any type.
+#[derive(Clone, Copy)]
+pub(crate) struct JoinRef<'a>(pub(crate) &'a dyn Render);
+
+impl core::fmt::Debug for JoinRef<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("JoinRef(..)")
+    }
+}
+
+impl<'a, I> From<&'a Join<'a, I>> for Twine<'a>
+where
+    I: IntoIterator + Clone + 'a,
+    I::Item: TwinePiece,
+{
+    #[inline(always)]
+    fn from(t: &'a Join<'a, I>) -> Twine<'a> {
+        Twine(TwineKind::Unary(TwineChild::Join(JoinRef(t))))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::string::ToString;
+
+    #[test]
+    fn separators() {
+        let items = ["a", "b", "c", "d"];
+        for (n, expected) in [
+            (0, "()"),
+            (1, "(a)"),
+            (2, "(a or b)"),
+            (3, "(a; b or c)"),
+            (4, "(a; b; c or d)"),
+        ] {
+            let join = Twine::join(&items[..n], "; ")
+                .last_separator(" or ")
+                .prefix(&'(')
+                .suffix(")");
+            let t = Twine::from(&join);
+            assert_eq!(t.to_string(), expected);
+            assert_eq!(t.len(), expected.len());
+            assert_eq!(t.estimated_capacity(), expected.len());
+            assert_eq!(t.is_empty(), expected.is_empty());
+        }
+    }
+
+    #[test]
+    fn nested_items() {
+        let x = 7u8;
+        let pair = Twine::from(("x=", "y"));
+        let value = Twine::from(&x);
+        let assignment = &pair + &value;
+        let parts = [assignment, Twine::empty(), Twine::hex(&x)];
+        let inner = Twine::join(&parts, "|");
+        let inner = Twine::from(&inner);
+        let twines = std::vec![inner, inner];
+        let outer = Twine::join(&twines, Twine::from(&'\n'));
+        let t = Twine::from(&outer);
+        assert_eq!(t.to_string(), "x=y7||7\nx=y7||7");
+        assert_eq!(t.estimated_capacity(), t.len());
+        #[cfg(feature = "alloc")]
+        {
+            let copy = crate::TwineBuf::from(&t);
+            assert_eq!(copy.to_string(), "x=y7||7\nx=y7||7");
+        }
+    }
+}
@@ -0,0 +1,148 @@
+//! Leaves that are rendered by closures, e.g. for pieces that are expensive to compute.
+
+use core::fmt::Write;
+
+use crate::{Twine, TwineChild, TwineKind};
+
+/// A closure that renders a leaf of a Twine, see `Twine::from_fn`.
+///
+/// Implemented for all closures `Fn(&mut dyn fmt::Write) -> fmt::Result`
+/// and for closures with a length, see `WithLen`.
+pub trait WriteFn {
+    /// Render the leaf into the writer.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `fmt::Error` Error Result if writing to `w` fails.
+    fn write_to(&self, w: &mut dyn Write) -> core::fmt::Result;
+
+    /// The exact length in bytes of the rendered leaf, if it is known without rendering it.
+    fn len_hint(&self) -> Option<usize> {
+        None
+    }
+}
+
+impl<F: Fn(&mut dyn Write) -> core::fmt::Result> WriteFn for F {
+    #[inline(always)]
+    fn write_to(&self, w: &mut dyn Write) -> core::fmt::Result {
+        self(w)
+    }
+}
+
+/// A closure that renders a leaf together with a closure that returns its length.
+///
+/// The length closure must return the exact length in bytes of what the render closure writes,
+/// it is used by `len` and `estimated_capacity` instead of rendering the leaf.
+///
+/// # Example
+/// ```
+/// # use twine::{Twine, WithLen};
+/// let demangled = WithLen::new(|w| w.write_str("core::fmt::write"), || 16);
+/// let t = Twine::from_fn(&demangled);
+/// assert_eq!(t.len(), 16);
+/// assert_eq!(t.estimated_capacity(), 16);
+/// assert_eq!(t.to_string(), "core::fmt::write");
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct WithLen<F, L> {
+    write: F,
+    len: L,
+}
+
+impl<F, L> WithLen<F, L>
+where
+    F: Fn(&mut dyn Write) -> core::fmt::Result,
+    L: Fn() -> usize,
+{
+    /// Combine a render closure with a closure that returns the length it renders.
+    #[inline(always)]
+    #[must_use]
+    pub fn new(write: F, len: L) -> Self {
+        WithLen { write, len }
+    }
+}
+
+impl<F, L> WriteFn for WithLen<F, L>
+where
+    F: Fn(&mut dyn Write) -> core::fmt::Result,
+    L: Fn() -> usize,
+{
+    #[inline(always)]
+    fn write_to(&self, w: &mut dyn Write) -> core::fmt::Result {
+        (self.write)(w)
+    }
+
+    #[inline(always)]
+    fn len_hint(&self) -> Option<usize> {
+        Some((self.len)())
+    }
+}
+
+/// A reference to a closure of any type.
+#[derive(Clone, Copy)]
+pub(crate) struct FnRef<'a>(pub(crate) &'a dyn WriteFn);
+
+impl core::fmt::Debug for FnRef<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("FnRef(..)")
+    }
+}
+
+impl<'a> Twine<'a> {
+    /// Create a new Twine that is rendered by a closure.
+    ///
+    /// The closure is only called when the Twine is measured or rendered,
+    /// and it is called again every time.
+    /// Without a length, see `WithLen`, `len` calls the closure to count the bytes it writes
+    /// and `estimated_capacity` assumes the leaf is empty.
+    ///
+    /// # Example
+    /// ```
+    /// # use twine::Twine;
+    /// use std::fmt::Write;
+    /// let path = ["usr", "lib"];
+    /// let render = |w: &mut dyn Write| path.iter().try_for_each(|p| write!(w, "/{p}"));
+    /// let t = Twine::from_fn(&render);
+    /// assert_eq!(t.to_string(), "/usr/lib");
+    /// assert_eq!(t.len(), 8);
+    /// ```
+    #[inline(always)]
+    #[must_use]
+    pub fn from_fn<F: WriteFn>(f: &'a F) -> Twine<'a> {
+        Twine(TwineKind::Unary(TwineChild::Fn(FnRef(f))))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use core::cell::Cell;
+    use std::string::ToString;
+
+    #[test]
+    fn lazy_calls() {
+        let calls = Cell::new(0);
+        let render = |w: &mut dyn Write| {
+            calls.set(calls.get() + 1);
+            w.write_str("expensive")
+        };
+        let lazy = Twine::from_fn(&render);
+        let hinted = WithLen::new(render, || 9);
+        let hinted = Twine::from_fn(&hinted);
+        assert_eq!(calls.get(), 0);
+        assert_eq!(hinted.len(), 9);
+        assert_eq!(hinted.estimated_capacity(), 9);
+        assert!(!hinted.is_empty());
+        assert_eq!(calls.get(), 0);
+        assert_eq!(lazy.len(), 9);
+        assert_eq!(lazy.estimated_capacity(), 0);
+        assert_eq!(calls.get(), 1);
+        crate::twine!(let t = lazy, ' ', hinted);
+        assert_eq!(t.to_string(), "expensive expensive");
+        #[cfg(feature = "alloc")]
+        {
+            let copy = crate::TwineBuf::from(&t);
+            assert_eq!(copy.to_string(), "expensive expensive");
+        }
+    }
+}
@@ -7,6 +7,8 @@
 //!
 //! Use `Twine::concat` to concatinate multiple Twines.
 //!
+//! Use the `twine!` macro to build a Twine from many pieces at once.
+//!
 //! Similar to LLVM's `llvm::Twine`.
 //!
 //! A Twine is a lightweight string structure.
@@ -20,18 +22,82 @@
 //! Twines have a special `null` values, which always yields a `null` value on concatination
 //! and is rendered an an empty string.
 //!
+//! ## Views
+//!
+//! Like `fmt::Arguments`, some values are only borrowed by the Twines created of them,
+//! e.g. `ConcatDisplay`, `Join`, `Pad` and `Truncate`.
+//! They have to be stored in a variable that outlives the Twine,
+//! so create them first and then create the Twine of a reference to them:
+//!
+//! ```
+//! # use twine::Twine;
+//! let n = 5u32;
+//! let n = Twine::from(&n);
+//! let pad = Twine::pad_left(&n, 3, '0');
+//! let t = Twine::from(&pad);
+//! assert_eq!(t.to_string(), "005");
+//! ```
+//!
 //! ## Features
 //!
-//! - `std`: enable support for `std::String`. Use `Twine::to_string_preallocating()` to render into a String.
+//! - `alloc`: enable support for `String`, `Box<str>`, `Cow<str>`, `Rc<str>` and `Arc<str>` without `std`.
+//!   Use `Twine::to_string_preallocating()` to render into a String.
+//!   Use `Twine::to_cow()` to avoid copying Twines that consist of a single str.
+//!   Use `Twine::to_cstring()` to render into a `CString`.
+//!   Use `TwineBuf` to store a copy of a Twine beyond the lifetime of its values.
+//! - `std`: enable `alloc` and support for `std` only types.
+//!   Use `Twine::write_io()` to render into a `std::io::Write`.
+//!   Use `OsString::from(&twine)` to render into an `OsString`.
+//! - `bumpalo`: enable support for `bumpalo::Bump` arenas.
+//!   Use `TwineArena` to build Twines in an arena and `Twine::to_bump_str()` to render into an arena.
 //!
 
 #![no_std]
 #![deny(unsafe_code)]
 #![allow(clippy::inline_always)]
 
-#[cfg(feature = "std")]
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(any(feature = "std", test))]
 extern crate std;
 
+mod buffer;
+#[cfg(feature = "bumpalo")]
+mod bump;
+mod digits;
+mod float;
+mod int;
+#[cfg(feature = "std")]
+mod io;
+mod iter;
+mod join;
+mod lazy;
+mod macros;
+#[cfg(feature = "alloc")]
+mod owned;
+mod pad;
+mod sealed;
+mod truncate;
+
+pub use buffer::{Buffer, StackBuffer};
+#[cfg(feature = "bumpalo")]
+pub use bump::TwineArena;
+pub use float::Float;
+pub use int::{IntFormat, Integer};
+pub use iter::{Bytes, Chars, Chunk, Chunks};
+pub use join::Join;
+pub use lazy::{WithLen, WriteFn};
+pub use macros::TwinePiece;
+#[cfg(feature = "alloc")]
+pub use owned::TwineBuf;
+pub use pad::Pad;
+pub use truncate::Truncate;
+
+use float::{FloatFormat, FloatRef};
+use int::IntRef;
+use join::JoinRef;
+use lazy::FnRef;
+
 /// The Twine lightweight data structure for efficiently representing the concatenation
 /// of temporary values as strings.
 #[derive(Debug, Clone, Copy)]
@@ -49,22 +115,136 @@ enum TwineKind<'a> {
 }
 
 /// Inner representation of a non-empty Twine
+///
+/// `Debug` can not be derived for trait objects,
+/// so they are wrapped in `DynDisplay`, `DynDebug`, `FnRef` and `JoinRef`, which implement it.
 #[derive(Debug, Clone, Copy)]
 enum TwineChild<'a> {
     Twine(&'a Twine<'a>),
     Str(&'a str),
     Char(&'a char),
-    DecUsize(&'a usize),
-    DecU64(&'a u64),
-    DecU32(&'a u32),
-    DecU16(&'a u16),
-    DecIsize(&'a isize),
-    DecI64(&'a i64),
-    DecI32(&'a i32),
-    DecI16(&'a i16),
-    HexU64(&'a u64),
-    HexUsize(&'a usize),
+    Dec(IntRef<'a>),
+    Radix(IntRef<'a>, u8, IntFormat),
+    Float(FloatRef<'a>, FloatFormat),
     FmtArgs(&'a core::fmt::Arguments<'a>),
+    Display(DynDisplay<'a>),
+    Debug(DynDebug<'a>),
+    Fn(FnRef<'a>),
+    Join(JoinRef<'a>),
+    Pad(&'a Pad<'a>),
+    Truncate(&'a Truncate<'a>),
+    /// The concatenation of strs, never nested.
+    Strs(&'a [&'a str]),
+    /// The concatenation of Twines, only ever the single child of a unary Twine.
+    Slice(&'a [Twine<'a>]),
+    /// A Twine repeated the given number of times, at least twice.
+    Repeat(&'a Twine<'a>, usize),
+    /// The leaves of an owned Twine, never nested.
+    #[cfg(feature = "alloc")]
+    Buf(&'a TwineBuf),
+}
+
+/// A reference to any `Display` value, rendered in the `Debug` output of Twines.
+#[derive(Clone, Copy)]
+struct DynDisplay<'a>(&'a dyn core::fmt::Display);
+
+impl core::fmt::Debug for DynDisplay<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_tuple("DynDisplay")
+            .field(&format_args!("{}", self.0))
+            .finish()
+    }
+}
+
+/// A reference to any `Debug` value, rendered in the `Debug` output of Twines.
+#[derive(Clone, Copy)]
+struct DynDebug<'a>(&'a dyn core::fmt::Debug);
+
+impl core::fmt::Debug for DynDebug<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_tuple("DynDebug")
+            .field(&format_args!("{:?}", self.0))
+            .finish()
+    }
+}
+
+/// Counts the bytes written to it.
+struct WriteCounter(usize);
+
+impl core::fmt::Write for WriteCounter {
+    #[inline]
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        self.0 += s.len();
+        Ok(())
+    }
+}
+
+/// The stack entries that a Twine nested in a leaf child costs, see `Twine::MAX_DEPTH`.
+const NESTED_COST: usize = 8;
+
+/// The stack entries left for the Twines nested in the leaf children of a Twine
+/// that is traversed with at most `depth` entries.
+#[inline(always)]
+const fn nested_depth(depth: usize) -> usize {
+    depth.saturating_sub(NESTED_COST)
+}
+
+/// A pending part of a Twine while it is traversed.
+#[derive(Debug, Clone, Copy)]
+enum Pending<'a> {
+    /// The top of a left spine and the number of spine nodes
+    /// whose right children are still pending, to be visited from the deepest node up.
+    Spine(&'a Twine<'a>, usize),
+    /// The remaining Twines of a slice, never empty.
+    Slice(&'a [Twine<'a>]),
+}
+
+/// A view of a slice that is rendered as the concatenation of its items.
+///
+/// Create a Twine of it with `Twine::from(&view)`, see [views](crate#views).
+///
+/// # Example
+/// ```
+/// # use twine::{ConcatDisplay, Twine};
+/// let values = [1.5, 2.0, 3.25];
+/// let view = ConcatDisplay::new(&values);
+/// let t = Twine::from(&view);
+/// assert_eq!(t.to_string(), "1.523.25");
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct ConcatDisplay<'a, T>(&'a [T]);
+
+impl<'a, T: core::fmt::Display> ConcatDisplay<'a, T> {
+    /// Create a new view of the items.
+    #[inline(always)]
+    #[must_use]
+    pub fn new(items: &'a [T]) -> Self {
+        ConcatDisplay(items)
+    }
+}
+
+impl<T: core::fmt::Display> core::fmt::Display for ConcatDisplay<'_, T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        self.0.iter().try_for_each(|item| item.fmt(f))
+    }
+}
+
+impl<'a, T: core::fmt::Display> From<&'a ConcatDisplay<'a, T>> for Twine<'a> {
+    #[inline(always)]
+    fn from(t: &'a ConcatDisplay<'a, T>) -> Twine<'a> {
+        if t.0.is_empty() {
+            Twine::empty()
+        } else {
+            Twine(TwineKind::Unary(TwineChild::Display(DynDisplay(t))))
+        }
+    }
+}
+
+impl<'a> From<&'a [&'a str]> for Twine<'a> {
+    #[inline(always)]
+    fn from(t: &'a [&'a str]) -> Twine<'a> {
+        Twine::concat_strs(t)
+    }
 }
 
 impl<'a> From<&'a str> for Twine<'a> {
@@ -78,74 +258,81 @@ impl<'a> From<&'a str> for Twine<'a> {
     }
 }
 
-#[cfg(feature = "std")]
-impl<'a> From<&'a std::string::String> for Twine<'a> {
+#[cfg(feature = "alloc")]
+impl<'a> From<&'a alloc::string::String> for Twine<'a> {
     #[inline(always)]
-    fn from(t: &'a std::string::String) -> Twine<'a> {
+    fn from(t: &'a alloc::string::String) -> Twine<'a> {
         Twine::from(t.as_str())
     }
 }
 
-impl<'a> From<&'a char> for Twine<'a> {
+#[cfg(feature = "alloc")]
+impl<'a> From<&'a alloc::boxed::Box<str>> for Twine<'a> {
     #[inline(always)]
-    fn from(t: &'a char) -> Twine<'a> {
-        Twine(TwineKind::Unary(TwineChild::Char(t)))
+    fn from(t: &'a alloc::boxed::Box<str>) -> Twine<'a> {
+        Twine::from(&**t)
     }
 }
 
-impl<'a> From<&'a usize> for Twine<'a> {
+#[cfg(feature = "alloc")]
+impl<'a> From<&'a alloc::borrow::Cow<'_, str>> for Twine<'a> {
     #[inline(always)]
-    fn from(t: &'a usize) -> Twine<'a> {
-        Twine(TwineKind::Unary(TwineChild::DecUsize(t)))
+    fn from(t: &'a alloc::borrow::Cow<'_, str>) -> Twine<'a> {
+        Twine::from(&**t)
     }
 }
 
-impl<'a> From<&'a u64> for Twine<'a> {
+#[cfg(feature = "alloc")]
+impl<'a> From<&'a alloc::rc::Rc<str>> for Twine<'a> {
     #[inline(always)]
-    fn from(t: &'a u64) -> Twine<'a> {
-        Twine(TwineKind::Unary(TwineChild::DecU64(t)))
+    fn from(t: &'a alloc::rc::Rc<str>) -> Twine<'a> {
+        Twine::from(&**t)
     }
 }
 
-impl<'a> From<&'a u32> for Twine<'a> {
+#[cfg(all(feature = "alloc", target_has_atomic = "ptr"))]
+impl<'a> From<&'a alloc::sync::Arc<str>> for Twine<'a> {
     #[inline(always)]
-    fn from(t: &'a u32) -> Twine<'a> {
-        Twine(TwineKind::Unary(TwineChild::DecU32(t)))
+    fn from(t: &'a alloc::sync::Arc<str>) -> Twine<'a> {
+        Twine::from(&**t)
     }
 }
 
-impl<'a> From<&'a u16> for Twine<'a> {
+impl<'a> From<&'a char> for Twine<'a> {
     #[inline(always)]
-    fn from(t: &'a u16) -> Twine<'a> {
-        Twine(TwineKind::Unary(TwineChild::DecU16(t)))
+    fn from(t: &'a char) -> Twine<'a> {
+        Twine(TwineKind::Unary(TwineChild::Char(t)))
     }
 }
 
-impl<'a> From<&'a isize> for Twine<'a> {
+impl<'a, T: Integer> From<&'a T> for Twine<'a> {
+    /// Create a new Twine that is rendered as the decimal value of the integer.
     #[inline(always)]
-    fn from(t: &'a isize) -> Twine<'a> {
-        Twine(TwineKind::Unary(TwineChild::DecIsize(t)))
+    fn from(t: &'a T) -> Twine<'a> {
+        Twine(TwineKind::Unary(TwineChild::Dec(t.int_ref())))
     }
 }
 
-impl<'a> From<&'a i64> for Twine<'a> {
+impl<'a> From<&'a f64> for Twine<'a> {
+    /// Create a new Twine that is rendered as the shortest representation that round-trips.
     #[inline(always)]
-    fn from(t: &'a i64) -> Twine<'a> {
-        Twine(TwineKind::Unary(TwineChild::DecI64(t)))
+    fn from(t: &'a f64) -> Twine<'a> {
+        Twine::float(t)
     }
 }
 
-impl<'a> From<&'a i32> for Twine<'a> {
+impl<'a> From<&'a f32> for Twine<'a> {
+    /// Create a new Twine that is rendered as the shortest representation that round-trips.
     #[inline(always)]
-    fn from(t: &'a i32) -> Twine<'a> {
-        Twine(TwineKind::Unary(TwineChild::DecI32(t)))
+    fn from(t: &'a f32) -> Twine<'a> {
+        Twine::float(t)
     }
 }
 
-impl<'a> From<&'a i16> for Twine<'a> {
+impl<'a> From<&'a bool> for Twine<'a> {
     #[inline(always)]
-    fn from(t: &'a i16) -> Twine<'a> {
-        Twine(TwineKind::Unary(TwineChild::DecI16(t)))
+    fn from(t: &'a bool) -> Twine<'a> {
+        Twine::bool_with(t, "true", "false")
     }
 }
 
@@ -156,6 +343,41 @@ impl<'a> From<&'a core::fmt::Arguments<'a>> for Twine<'a> {
     }
 }
 
+/// Implement `From` for references to types that are rendered with their `Display` implementation
+macro_rules! impl_from_display {
+    ($($(#[$cfg:meta])* $t:ty),* $(,)?) => {
+        $(
+            $(#[$cfg])*
+            impl<'a> From<&'a $t> for Twine<'a> {
+                #[inline(always)]
+                fn from(t: &'a $t) -> Twine<'a> {
+                    Twine::display(t)
+                }
+            }
+        )*
+    };
+}
+
+impl_from_display! {
+    core::net::IpAddr,
+    core::net::Ipv4Addr,
+    core::net::Ipv6Addr,
+    core::net::SocketAddr,
+    core::net::SocketAddrV4,
+    core::net::SocketAddrV6,
+    #[cfg(feature = "std")]
+    std::path::Display<'a>,
+}
+
+impl<'a> From<&'a core::time::Duration> for Twine<'a> {
+    /// Render the duration with its `Debug` implementation, like `1.5s`,
+    /// as it does not implement `Display`.
+    #[inline(always)]
+    fn from(t: &'a core::time::Duration) -> Twine<'a> {
+        Twine::debug(t)
+    }
+}
+
 impl<'a> From<(&'a str, &'a str)> for Twine<'a> {
     #[inline(always)]
     fn from((lhs, rhs): (&'a str, &'a str)) -> Twine<'a> {
@@ -216,7 +438,7 @@ impl<'a> Twine<'a> {
     #[inline(always)]
     #[must_use]
     pub fn hex_u64(t: &'a u64) -> Twine<'a> {
-        Twine(TwineKind::Unary(TwineChild::HexU64(t)))
+        Twine::hex(t)
     }
 
     /// Create a new Twine that is rendered as the hexadecimal value of the input.
@@ -230,293 +452,1197 @@ impl<'a> Twine<'a> {
     #[inline(always)]
     #[must_use]
     pub fn hex_usize(t: &'a usize) -> Twine<'a> {
-        Twine(TwineKind::Unary(TwineChild::HexUsize(t)))
-    }
-
-    /// Flatten a nested unary Twine
-    #[inline(always)]
-    fn flatten(&'a self) -> &'a Twine<'a> {
-        match self.0 {
-            // TODO: better flattening by moving flatten to TwineChild
-            TwineKind::Unary(TwineChild::Twine(t)) => t,
-            _ => self,
-        }
-    }
-
-    /// Create a new Twine by concatinating two Twines.
-    #[inline(always)]
-    #[must_use]
-    pub fn new_concat(lhs: &'a Twine<'a>, rhs: &'a Twine<'a>) -> Twine<'a> {
-        match (lhs.flatten().0, rhs.flatten().0) {
-            (TwineKind::Null, _) | (_, TwineKind::Null) => Twine(TwineKind::Null),
-            (TwineKind::Empty, _) => *rhs,
-            (_, TwineKind::Empty) => *lhs,
-            (TwineKind::Unary(l), TwineKind::Unary(r)) => Twine(TwineKind::Binary(l, r)),
-            _ => Twine(TwineKind::Binary(
-                TwineChild::Twine(lhs),
-                TwineChild::Twine(rhs),
-            )),
-        }
+        Twine::hex(t)
     }
 
-    /// Create a new Twine by concatinating another Twine to this one.
+    /// Create a new Twine that is rendered as the hexadecimal value of an integer of any width.
     ///
-    /// Concatinating a `null` value always returns a null value
+    /// Negative integers are rendered as their two's complement, like the `{:x}` format.
     ///
     /// # Example
     /// ```
     /// # use twine::Twine;
-    /// let a = &Twine::from("1234");
-    /// let b = &Twine::from(&56u32);
-    /// let c = a.concat(b);
-    /// assert_eq!(c.to_string(), "123456");
-    /// let null = &Twine::null();
-    /// let d = a.concat(null);
-    /// assert_eq!(d.is_null(), true);
-    /// assert_eq!(d.to_string(), "");
+    /// # use core::num::NonZeroU8;
+    /// assert_eq!(Twine::hex(&0x42u8).to_string(), "42");
+    /// assert_eq!(Twine::hex(&u128::MAX).to_string(), "ffffffffffffffffffffffffffffffff");
+    /// assert_eq!(Twine::hex(&-1i16).to_string(), "ffff");
+    /// assert_eq!(Twine::hex(&NonZeroU8::new(0xab).unwrap()).to_string(), "ab");
     /// ```
     #[inline(always)]
     #[must_use]
-    pub fn concat(&'a self, other: &'a Twine<'a>) -> Twine<'a> {
-        Twine::new_concat(self, other)
+    pub fn hex<T: Integer>(t: &'a T) -> Twine<'a> {
+        Twine::hex_with(t, IntFormat::new())
     }
 
-    /// Checks if the Twine has 0 childs
+    /// Create a new Twine that is rendered as the hexadecimal value of an integer of any width
+    /// with the given formatting options for width, padding, case, prefix and digit separators.
     ///
     /// # Example
     /// ```
-    /// # use twine::Twine;
-    /// assert_eq!(Twine::null().is_nullary(), true);
-    /// assert_eq!(Twine::empty().is_nullary(), true);
-    /// assert_eq!(Twine::from("foo").is_nullary(), false);
+    /// # use twine::{IntFormat, Twine};
+    /// let address = IntFormat::new().width(8).zero_pad(true).prefix(true).separator('_', 4);
+    /// assert_eq!(Twine::hex_with(&0x7fffu64, address).to_string(), "0x0000_7fff");
+    /// let byte = IntFormat::new().width(2).zero_pad(true).uppercase(true);
+    /// assert_eq!(Twine::hex_with(&10u8, byte).to_string(), "0A");
+    /// let aligned = IntFormat::new().width(6).prefix(true);
+    /// assert_eq!(Twine::hex_with(&0xabcu16, aligned).to_string(), "   0xabc");
     /// ```
     #[inline(always)]
     #[must_use]
-    pub fn is_nullary(&self) -> bool {
-        matches!(self.0, TwineKind::Empty | TwineKind::Null)
+    pub fn hex_with<T: Integer>(t: &'a T, format: IntFormat) -> Twine<'a> {
+        Twine::radix_with(t, 16, format)
     }
 
-    /// Checks if the Twine has 1 childs
+    /// Create a new Twine that is rendered as the octal value of an integer of any width.
+    ///
+    /// Negative integers are rendered as their two's complement, like the `{:o}` format.
     ///
     /// # Example
     /// ```
     /// # use twine::Twine;
-    /// assert_eq!(Twine::null().is_unary(), false);
-    /// assert_eq!(Twine::empty().is_unary(), false);
-    /// assert_eq!(Twine::from("foo").is_unary(), true);
-    /// assert_eq!(Twine::from(("foo", "bar")).is_unary(), false);
+    /// assert_eq!(Twine::octal(&0o755u32).to_string(), "755");
+    /// assert_eq!(Twine::octal(&-1i8).to_string(), "377");
     /// ```
     #[inline(always)]
     #[must_use]
-    pub fn is_unary(&self) -> bool {
-        matches!(self.0, TwineKind::Unary(_))
+    pub fn octal<T: Integer>(t: &'a T) -> Twine<'a> {
+        Twine::octal_with(t, IntFormat::new())
     }
 
-    /// Checks if the Twine has 2 childs
+    /// Create a new Twine that is rendered as the octal value of an integer of any width
+    /// with the given formatting options, the prefix is `0o`.
     ///
     /// # Example
     /// ```
-    /// # use twine::Twine;
-    /// assert_eq!(Twine::null().is_binary(), false);
-    /// assert_eq!(Twine::empty().is_binary(), false);
-    /// assert_eq!(Twine::from("foo").is_binary(), false);
-    /// assert_eq!(Twine::from(("foo", "bar")).is_binary(), true);
+    /// # use twine::{IntFormat, Twine};
+    /// let mode = IntFormat::new().width(4).zero_pad(true).prefix(true);
+    /// assert_eq!(Twine::octal_with(&0o644u16, mode).to_string(), "0o0644");
     /// ```
     #[inline(always)]
     #[must_use]
-    pub fn is_binary(&self) -> bool {
-        matches!(self.0, TwineKind::Binary(_, _))
+    pub fn octal_with<T: Integer>(t: &'a T, format: IntFormat) -> Twine<'a> {
+        Twine::radix_with(t, 8, format)
     }
 
-    /// Checks if the Twine is a null value
+    /// Create a new Twine that is rendered as the binary value of an integer of any width.
+    ///
+    /// Negative integers are rendered as their two's complement, like the `{:b}` format.
     ///
     /// # Example
     /// ```
     /// # use twine::Twine;
-    /// assert_eq!(Twine::null().is_null(), true);
-    /// assert_eq!(Twine::empty().is_null(), false);
-    /// assert_eq!(Twine::from("foo").is_null(), false);
-    /// assert_eq!(Twine::from(("foo", "bar")).is_null(), false);
-    /// ````
+    /// assert_eq!(Twine::binary(&5u8).to_string(), "101");
+    /// assert_eq!(Twine::binary(&-2i8).to_string(), "11111110");
+    /// ```
     #[inline(always)]
     #[must_use]
-    pub fn is_null(&self) -> bool {
-        matches!(self.0, TwineKind::Null)
+    pub fn binary<T: Integer>(t: &'a T) -> Twine<'a> {
+        Twine::binary_with(t, IntFormat::new())
     }
 
-    /// Checks if the Twine is a single, possibly empty, str
+    /// Create a new Twine that is rendered as the binary value of an integer of any width
+    /// with the given formatting options, the prefix is `0b`.
     ///
     /// # Example
     /// ```
-    /// # use twine::Twine;
-    /// assert_eq!(Twine::null().is_single_str(), false);
-    /// assert_eq!(Twine::empty().is_single_str(), true);
-    /// assert_eq!(Twine::from("").is_single_str(), true);
-    /// assert_eq!(Twine::from("foo").is_single_str(), true);
-    /// assert_eq!(Twine::from(("foo", "bar")).is_single_str(), false);
+    /// # use twine::{IntFormat, Twine};
+    /// let mask = IntFormat::new().width(8).zero_pad(true).prefix(true).separator('_', 4);
+    /// assert_eq!(Twine::binary_with(&0x2cu8, mask).to_string(), "0b0010_1100");
     /// ```
     #[inline(always)]
     #[must_use]
-    pub fn is_single_str(&self) -> bool {
-        self.as_single_str().is_some()
+    pub fn binary_with<T: Integer>(t: &'a T, format: IntFormat) -> Twine<'a> {
+        Twine::radix_with(t, 2, format)
     }
 
-    /// Returns the Twine as a single str if it only contains one str.
+    /// Create a new Twine that is rendered as the value of an integer of any width
+    /// in the given radix, using the letters `a` to `z` for digits above 9.
+    ///
+    /// Negative integers are rendered with a `-` in front of their absolute value,
+    /// except in radix 2, 8 and 16, in which they are rendered as their two's complement
+    /// like the `{:b}`, `{:o}` and `{:x}` formats.
+    ///
+    /// # Panics
+    /// Panics if `radix` is not in the range from 2 to 36.
     ///
     /// # Example
     /// ```
     /// # use twine::Twine;
-    /// assert_eq!(Twine::null().as_single_str(), None);
-    /// assert_eq!(Twine::empty().as_single_str(), Some(""));
-    /// assert_eq!(Twine::from("").as_single_str(), Some(""));
-    /// assert_eq!(Twine::from("foo").as_single_str(), Some("foo"));
-    /// assert_eq!(Twine::from(("foo", "bar")).as_single_str(), None);
+    /// assert_eq!(Twine::radix(&35u8, 36).to_string(), "z");
+    /// assert_eq!(Twine::radix(&100u32, 3).to_string(), "10201");
+    /// assert_eq!(Twine::radix(&-100i32, 3).to_string(), "-10201");
+    /// assert_eq!(Twine::radix(&-1i8, 16).to_string(), "ff");
     /// ```
     #[inline(always)]
     #[must_use]
-    pub fn as_single_str(&self) -> Option<&'a str> {
-        match self.0 {
-            TwineKind::Empty => Some(""),
-            TwineKind::Unary(TwineChild::Str(s)) => Some(s),
-            _ => None,
-        }
+    pub fn radix<T: Integer>(t: &'a T, radix: u32) -> Twine<'a> {
+        Twine::radix_with(t, radix, IntFormat::new())
     }
 
-    /// Checks if the Twine is trivially empty because it does not have children.
-    /// Even if false, the Twine still might render to an empty string.
+    /// Create a new Twine that is rendered as the value of an integer of any width
+    /// in the given radix with the given formatting options.
     ///
-    /// To check if the twine actually renders to an empty string use `is_empty()`
+    /// The prefix is `0b`, `0o` or `0x` for radix 2, 8 and 16, other radixes have no prefix.
+    /// The `-` of a negative integer is rendered in front of the zero padding and is not counted in the width.
+    ///
+    /// # Panics
+    /// Panics if `radix` is not in the range from 2 to 36.
     ///
     /// # Example
     /// ```
-    /// # use twine::Twine;
-    /// assert_eq!(Twine::null().is_trivially_empty(), true);
-    /// assert_eq!(Twine::empty().is_trivially_empty(), true);
-    /// assert_eq!(Twine::from("").is_trivially_empty(), true);
-    /// assert_eq!(Twine::from("foo").is_trivially_empty(), false);
-    /// assert_eq!(Twine::from(("foo", "bar")).is_trivially_empty(), false);
-    /// assert_eq!(Twine::from(("", "")).is_trivially_empty(), true);
+    /// # use twine::{IntFormat, Twine};
+    /// let format = IntFormat::new().width(6).uppercase(true).separator(' ', 3);
+    /// assert_eq!(Twine::radix_with(&1295u32, 36, format).to_string(), "     ZZ");
     /// ```
-    #[inline(always)]
+    #[inline]
     #[must_use]
-    pub fn is_trivially_empty(&self) -> bool {
-        self.is_nullary()
+    pub fn radix_with<T: Integer>(t: &'a T, radix: u32, format: IntFormat) -> Twine<'a> {
+        assert!(
+            (2..=36).contains(&radix),
+            "radix must be in the range from 2 to 36, got {radix}"
+        );
+        // the radix is at most 36 and always fits into a u8
+        Twine(TwineKind::Unary(TwineChild::Radix(
+            t.int_ref(),
+            radix as u8,
+            format,
+        )))
     }
 
-    /// Check if the Twine actually renders to an empty string.
-    /// This requires actually rendering parts of the twine and might need allocations.
+    /// Create a new Twine that is rendered as the shortest representation of the float
+    /// that round-trips, like the `{}` format.
     ///
     /// # Example
     /// ```
     /// # use twine::Twine;
-    /// assert_eq!(Twine::null().is_empty(), true);
-    /// assert_eq!(Twine::empty().is_empty(), true);
-    /// assert_eq!(Twine::from("").is_empty(), true);
-    /// assert_eq!(Twine::from("foo").is_empty(), false);
-    /// assert_eq!(Twine::from(("foo", "bar")).is_empty(), false);
-    /// assert_eq!(Twine::from(("", "")).is_empty(), true);
+    /// assert_eq!(Twine::float(&0.1f64).to_string(), "0.1");
+    /// assert_eq!(Twine::float(&0.1f32).to_string(), "0.1");
+    /// assert_eq!(Twine::float(&-1e20f64).to_string(), "-100000000000000000000");
     /// ```
+    #[inline(always)]
     #[must_use]
-    pub fn is_empty(&self) -> bool {
-        struct WriteCounter(usize);
-        impl core::fmt::Write for WriteCounter {
-            fn write_str(&mut self, s: &str) -> core::fmt::Result {
-                self.0 += s.len();
-                Ok(())
-            }
-        }
-        let mut w = WriteCounter(0);
-        let _ = self.write_to(&mut w);
-        w.0 == 0
+    pub fn float<T: Float>(t: &'a T) -> Twine<'a> {
+        Twine(TwineKind::Unary(TwineChild::Float(
+            t.float_ref(),
+            FloatFormat::Shortest,
+        )))
     }
 
-    /// The estimated capacity needed to store the Twine as a String.
-    /// This method returns a vague lower bound needed.
-    ///
-    /// Use `next_power_of_two()` on the return value to enable efficient allocations
-    /// and reduce re-allocations.
+    /// Create a new Twine that is rendered as the float
+    /// with `precision` digits after the decimal point, like the `{:.precision$}` format.
     ///
     /// # Example
     /// ```
     /// # use twine::Twine;
-    /// assert_eq!(Twine::null().estimated_capacity(), 0);
-    /// assert_eq!(Twine::empty().estimated_capacity(), 0);
-    /// assert_eq!(Twine::from("").estimated_capacity(), 0);
-    /// assert_eq!(Twine::from("foo").estimated_capacity(), 3);
-    /// assert_eq!(Twine::from(("foo", "bar")).estimated_capacity(), 6);
-    /// assert_eq!(Twine::from(("", "")).estimated_capacity(), 0);
-    /// assert!(Twine::from(&42u32).estimated_capacity() >= 1);
+    /// assert_eq!(Twine::float_fixed(&3.14159f64, 2).to_string(), "3.14");
+    /// assert_eq!(Twine::float_fixed(&2.5f32, 0).to_string(), "2");
+    /// assert_eq!(Twine::float_fixed(&1f64, 3).to_string(), "1.000");
     /// ```
+    #[inline(always)]
     #[must_use]
-    pub fn estimated_capacity(&self) -> usize {
-        match self.0 {
-            TwineKind::Null | TwineKind::Empty => 0,
-            TwineKind::Unary(child) => child.estimated_capacity(),
-            TwineKind::Binary(l_child, r_child) => {
-                l_child.estimated_capacity() + r_child.estimated_capacity()
-            }
-        }
+    pub fn float_fixed<T: Float>(t: &'a T, precision: u16) -> Twine<'a> {
+        Twine(TwineKind::Unary(TwineChild::Float(
+            t.float_ref(),
+            FloatFormat::Fixed(precision),
+        )))
     }
 
-    /// Render the Twine as a string in the buffer of the writer.
-    ///
-    /// # Errors
-    ///
-    /// This method returns an `fmt::Error` Error Result if writing to `w` fails.
+    /// Create a new Twine that is rendered as the shortest representation of the float
+    /// that round-trips in scientific notation, like the `{:e}` format.
     ///
     /// # Example
     /// ```
     /// # use twine::Twine;
-    /// let a = &Twine::from("1234");
-    /// let b = &Twine::from(&56u32);
-    /// let c = a.concat(b);
-    /// let mut s = String::new();
-    /// c.write_to(&mut s);
-    /// assert_eq!(s, "123456");
+    /// assert_eq!(Twine::float_exp(&1234.5f64).to_string(), "1.2345e3");
+    /// assert_eq!(Twine::float_exp(&0.001f32).to_string(), "1e-3");
     /// ```
-    pub fn write_to<W: core::fmt::Write>(&self, w: &mut W) -> core::fmt::Result {
+    #[inline(always)]
+    #[must_use]
+    pub fn float_exp<T: Float>(t: &'a T) -> Twine<'a> {
+        Twine(TwineKind::Unary(TwineChild::Float(
+            t.float_ref(),
+            FloatFormat::Exp,
+        )))
+    }
+
+    /// Flatten a nested unary Twine
+    #[inline(always)]
+    fn flatten(&'a self) -> &'a Twine<'a> {
         match self.0 {
-            TwineKind::Null | TwineKind::Empty => {}
-            TwineKind::Unary(child) => child.write_to(w)?,
-            TwineKind::Binary(l_child, r_child) => {
-                l_child.write_to(w)?;
-                r_child.write_to(w)?;
-            }
-        };
-        Ok(())
+            // TODO: better flattening by moving flatten to TwineChild
+            TwineKind::Unary(TwineChild::Twine(t)) => t,
+            _ => self,
+        }
     }
 
-    /// Converts the given Twine to a String
+    /// The maximum nesting depth supported when rendering or measuring a Twine.
     ///
-    /// Specialization of the `to_string()` method that pre-allocates an estimated capacity
+    /// Twines are traversed with a fixed-size explicit stack instead of recursion,
+    /// so the call stack needed to render them is bounded.
+    /// Chains built by repeatedly concatinating onto either side, e.g. `acc = &acc + &piece`
+    /// or `acc = &piece + &acc`, need at most a few entries per 2x of length
+    /// and can therefore be arbitrarily long.
+    /// Every other level of nesting, like a node whose right child is nested
+    /// below a node whose left child is nested, needs one entry while it is traversed.
+    /// Twines nested in joins, paddings, truncations and repeats are traversed with an explicit stack of their own
+    /// and 8 entries less than the Twine they are nested in, so at most 7 of them can be nested in each other.
+    /// Rendering the deepest Twines needs about 40 KiB of call stack in optimized builds
+    /// and about 80 KiB in debug builds on x86_64.
     ///
-    /// # Panics
+    /// If a Twine needs more than `MAX_DEPTH` entries, `write_to` returns an `fmt::Error`,
+    /// `is_empty` returns `false`
+    /// and `estimated_capacity` only accounts for the part that could be visited.
+    pub const MAX_DEPTH: usize = 64;
+
+    /// Walk one step down the left spine of the Twine.
     ///
-    /// This method panics if it fails to write to the String it allocates.
+    /// Returns the nested left Twine and the right child of this node, if there is one.
+    #[inline(always)]
+    fn left_step(&self) -> Option<(&'a Twine<'a>, Option<&TwineChild<'a>>)> {
+        match self.0 {
+            TwineKind::Unary(TwineChild::Twine(t)) => Some((t, None)),
+            TwineKind::Binary(TwineChild::Twine(t), ref r_child) => Some((t, Some(r_child))),
+            _ => None,
+        }
+    }
+
+    /// Walk `n` steps down the left spine of the Twine.
+    #[inline(always)]
+    fn nth_left(&'a self, n: usize) -> &'a Twine<'a> {
+        let mut t = self;
+        for _ in 0..n {
+            match t.left_step() {
+                Some((next, _)) => t = next,
+                None => break,
+            }
+        }
+        t
+    }
+
+    /// Visit all leaf children of the Twine in rendering order.
     ///
-    /// # Example
-    /// ```
-    /// # use twine::Twine;
-    /// let a = &Twine::from("1234");
-    /// let b = &Twine::from(&56u32);
+    /// Nested Twines are never passed to `f`, owned Twines are passed as a whole,
+    /// so that their repeated leaves can be measured without visiting them again.
+    /// Returns `overflow` if the Twine needs more than `depth` stack entries, see `MAX_DEPTH`,
+    /// and stops at the first error returned by `f`.
+    #[inline]
+    fn try_for_each_leaf<E>(
+        &'a self,
+        depth: usize,
+        overflow: E,
+        mut f: impl FnMut(&TwineChild<'a>) -> Result<(), E>,
+    ) -> Result<(), E> {
+        if depth == 0 {
+            return Err(overflow);
+        }
+        // visit the strs instead of the flat container itself
+        let mut f = move |child: &TwineChild<'a>| match child {
+            TwineChild::Strs(strs) => strs.iter().try_for_each(|s| f(&TwineChild::Str(s))),
+            leaf => f(leaf),
+        };
+        // fast path without setting up the explicit stack while there are no nested left children
+        let mut node = self;
+        loop {
+            match node.0 {
+                TwineKind::Null | TwineKind::Empty => return Ok(()),
+                TwineKind::Unary(TwineChild::Twine(_) | TwineChild::Slice(_))
+                | TwineKind::Binary(TwineChild::Twine(_), _) => {
+                    return node.try_for_each_leaf_nested(depth, overflow, f)
+                }
+                TwineKind::Unary(ref child) => return f(child),
+                TwineKind::Binary(ref l_child, TwineChild::Twine(t)) => {
+                    f(l_child)?;
+                    node = t;
+                }
+                TwineKind::Binary(ref l_child, ref r_child) => {
+                    f(l_child)?;
+                    return f(r_child);
+                }
+            }
+        }
+    }
+
+    /// Visit all leaf children of a Twine with nested left children
+    /// using at most `depth` entries of an explicit stack.
+    fn try_for_each_leaf_nested<E>(
+        &'a self,
+        depth: usize,
+        overflow: E,
+        mut f: impl FnMut(&TwineChild<'a>) -> Result<(), E>,
+    ) -> Result<(), E> {
+        /// Left spines longer than this are split in half instead of walked once per child.
+        const SPLIT_LEN: usize = 8;
+
+        let mut stack: [Option<Pending<'a>>; Twine::MAX_DEPTH] = [None; Twine::MAX_DEPTH];
+        let mut top = 0;
+        let mut next = Some(self);
+
+        loop {
+            if let Some(node) = next.take() {
+                let mut len = 0;
+                let mut bottom = node;
+                while let Some((t, _)) = bottom.left_step() {
+                    bottom = t;
+                    len += 1;
+                }
+                if len > 0 {
+                    if top == depth {
+                        return Err(overflow);
+                    }
+                    stack[top] = Some(Pending::Spine(node, len));
+                    top += 1;
+                }
+                match &bottom.0 {
+                    TwineKind::Null | TwineKind::Empty => {}
+                    TwineKind::Unary(TwineChild::Slice(ts)) => {
+                        if let Some((first, rest)) = ts.split_first() {
+                            if !rest.is_empty() {
+                                if top == depth {
+                                    return Err(overflow);
+                                }
+                                stack[top] = Some(Pending::Slice(rest));
+                                top += 1;
+                            }
+                            next = Some(first);
+                        }
+                    }
+                    TwineKind::Unary(child) => f(child)?,
+                    TwineKind::Binary(l_child, r_child) => {
+                        f(l_child)?;
+                        match r_child {
+                            TwineChild::Twine(t) => next = Some(*t),
+                            leaf => f(leaf)?,
+                        }
+                    }
+                }
+                continue;
+            }
+
+            if top == 0 {
+                return Ok(());
+            }
+            top -= 1;
+            let (spine, len) = match stack[top].take() {
+                Some(Pending::Spine(spine, len)) => (spine, len),
+                Some(Pending::Slice(ts)) => {
+                    let (first, rest) = ts.split_first().expect("pending slices are never empty");
+                    if !rest.is_empty() {
+                        stack[top] = Some(Pending::Slice(rest));
+                        top += 1;
+                    }
+                    next = Some(first);
+                    continue;
+                }
+                None => unreachable!("occupied stack entries are always Some"),
+            };
+            if len > SPLIT_LEN {
+                // visit the deeper half first and keep the upper half for later
+                let mid = len / 2;
+                stack[top] = Some(Pending::Spine(spine, mid));
+                top += 1;
+                if top == depth {
+                    return Err(overflow);
+                }
+                stack[top] = Some(Pending::Spine(spine.nth_left(mid), len - mid));
+                top += 1;
+            } else {
+                if len > 1 {
+                    stack[top] = Some(Pending::Spine(spine, len - 1));
+                    top += 1;
+                }
+                if let Some((_, Some(r_child))) = spine.nth_left(len - 1).left_step() {
+                    match r_child {
+                        TwineChild::Twine(t) => next = Some(*t),
+                        leaf => f(leaf)?,
+                    }
+                }
+            }
+        }
+    }
+
+    /// Create a new Twine by concatinating two Twines.
+    #[inline(always)]
+    #[must_use]
+    pub fn new_concat(lhs: &'a Twine<'a>, rhs: &'a Twine<'a>) -> Twine<'a> {
+        match (lhs.flatten().0, rhs.flatten().0) {
+            (TwineKind::Null, _) | (_, TwineKind::Null) => Twine(TwineKind::Null),
+            (TwineKind::Empty, _) => *rhs,
+            (_, TwineKind::Empty) => *lhs,
+            // slices stay the single child of their Twine to be found by the traversal
+            (TwineKind::Unary(l), TwineKind::Unary(r))
+                if !matches!(l, TwineChild::Slice(_)) && !matches!(r, TwineChild::Slice(_)) =>
+            {
+                Twine(TwineKind::Binary(l, r))
+            }
+            _ => Twine(TwineKind::Binary(
+                TwineChild::Twine(lhs),
+                TwineChild::Twine(rhs),
+            )),
+        }
+    }
+
+    /// Create a new Twine by concatinating another Twine to this one.
+    ///
+    /// Concatinating a `null` value always returns a null value
+    ///
+    /// # Example
+    /// ```
+    /// # use twine::Twine;
+    /// let a = &Twine::from("1234");
+    /// let b = &Twine::from(&56u32);
+    /// let c = a.concat(b);
+    /// assert_eq!(c.to_string(), "123456");
+    /// let null = &Twine::null();
+    /// let d = a.concat(null);
+    /// assert_eq!(d.is_null(), true);
+    /// assert_eq!(d.to_string(), "");
+    /// ```
+    #[inline(always)]
+    #[must_use]
+    pub fn concat(&'a self, other: &'a Twine<'a>) -> Twine<'a> {
+        Twine::new_concat(self, other)
+    }
+
+    /// Create a new Twine of any `Display` value.
+    ///
+    /// The value is rendered every time the Twine is measured or rendered.
+    ///
+    /// # Example
+    /// ```
+    /// # use twine::Twine;
+    /// struct Celsius(f32);
+    /// impl std::fmt::Display for Celsius {
+    ///     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    ///         write!(f, "{}°C", self.0)
+    ///     }
+    /// }
+    /// let temperature = Celsius(21.5);
+    /// let t = Twine::display(&temperature);
+    /// assert_eq!(t.to_string(), "21.5°C");
+    /// assert_eq!(t.len(), 7);
+    /// ```
+    #[inline(always)]
+    #[must_use]
+    pub fn display(t: &'a dyn core::fmt::Display) -> Twine<'a> {
+        Twine(TwineKind::Unary(TwineChild::Display(DynDisplay(t))))
+    }
+
+    /// Create a new Twine of any `Debug` value.
+    ///
+    /// The value is rendered every time the Twine is measured or rendered.
+    ///
+    /// # Example
+    /// ```
+    /// # use twine::Twine;
+    /// let value = Some("x");
+    /// assert_eq!(Twine::debug(&value).to_string(), "Some(\"x\")");
+    /// ```
+    #[inline(always)]
+    #[must_use]
+    pub fn debug(t: &'a dyn core::fmt::Debug) -> Twine<'a> {
+        Twine(TwineKind::Unary(TwineChild::Debug(DynDebug(t))))
+    }
+
+    /// Create a new Twine of a bool with custom spellings for `true` and `false`.
+    ///
+    /// Use `Twine::from(&b)` for the spellings `true` and `false`.
+    ///
+    /// # Example
+    /// ```
+    /// # use twine::Twine;
+    /// let enabled = true;
+    /// assert_eq!(Twine::bool_with(&enabled, "yes", "no").to_string(), "yes");
+    /// assert_eq!(Twine::from(&!enabled).to_string(), "false");
+    /// ```
+    #[inline(always)]
+    #[must_use]
+    pub fn bool_with(
+        b: &bool,
+        if_true: impl Into<Twine<'a>>,
+        if_false: impl Into<Twine<'a>>,
+    ) -> Twine<'a> {
+        if *b {
+            if_true.into()
+        } else {
+            if_false.into()
+        }
+    }
+
+    /// Create a new Twine of an optional value, or of the fallback if there is none.
+    ///
+    /// Accepts the same values as the `twine!` macro.
+    ///
+    /// # Example
+    /// ```
+    /// # use twine::Twine;
+    /// let port = Some(8080u16);
+    /// assert_eq!(Twine::optional(&port, "<none>").to_string(), "8080");
+    /// let user: Option<&str> = None;
+    /// assert_eq!(Twine::optional(&user, "<none>").to_string(), "<none>");
+    /// ```
+    #[inline(always)]
+    #[must_use]
+    pub fn optional<T: TwinePiece>(
+        value: &'a Option<T>,
+        fallback: impl Into<Twine<'a>>,
+    ) -> Twine<'a> {
+        match value {
+            Some(value) => value.to_twine(),
+            None => fallback.into(),
+        }
+    }
+
+    /// Create a new Twine of the concatenation of all Twines in the slice.
+    ///
+    /// The Twine is a single node that refers to the slice,
+    /// so neither nodes are allocated nor grows the depth with the number of Twines.
+    /// Concatenating a `null` value always returns a null value.
+    ///
+    /// # Example
+    /// ```
+    /// # use twine::Twine;
+    /// let n = 42u32;
+    /// let parts = [Twine::from("n = "), Twine::from(&n), Twine::from(("; ", "done"))];
+    /// assert_eq!(Twine::concat_all(&parts).to_string(), "n = 42; done");
+    /// assert_eq!(Twine::concat_all(&[]).is_empty(), true);
+    /// assert_eq!(Twine::concat_all(&[Twine::from("a"), Twine::null()]).is_null(), true);
+    /// ```
+    #[must_use]
+    pub fn concat_all(ts: &'a [Twine<'a>]) -> Twine<'a> {
+        if ts.iter().any(|t| t.flatten().is_null()) {
+            return Twine::null();
+        }
+        match ts {
+            [] => Twine::empty(),
+            [t] => *t,
+            _ => Twine(TwineKind::Unary(TwineChild::Slice(ts))),
+        }
+    }
+
+    /// Create a new Twine that renders the Twine `count` times.
+    ///
+    /// The Twine is a single node that refers to the repeated Twine, nothing is copied or rendered up front.
+    /// Its length is measured once and multiplied by `count`.
+    /// Repeating a `null` value always returns a null value.
+    ///
+    /// # Example
+    /// ```
+    /// # use twine::Twine;
+    /// let indent = Twine::from("  ");
+    /// let line = Twine::from("return;");
+    /// let indentation = Twine::repeat(&indent, 3);
+    /// let t = indentation.concat(&line);
+    /// assert_eq!(t.to_string(), "      return;");
+    /// let rule = Twine::from(&'-');
+    /// assert_eq!(Twine::repeat(&rule, 1_000_000_000_000).len(), 1_000_000_000_000);
+    /// assert_eq!(Twine::repeat(&rule, 0).is_empty(), true);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn repeat(t: &'a Twine<'a>, count: usize) -> Twine<'a> {
+        match (t.flatten().0, count) {
+            (TwineKind::Null, _) => Twine::null(),
+            (TwineKind::Empty, _) | (_, 0) => Twine::empty(),
+            (_, 1) => *t,
+            _ => Twine(TwineKind::Unary(TwineChild::Repeat(t, count))),
+        }
+    }
+
+    /// Create a new Twine of the concatenation of all strs in the slice.
+    ///
+    /// The Twine is a single node that refers to the slice.
+    ///
+    /// # Example
+    /// ```
+    /// # use twine::Twine;
+    /// let words = ["con", "cat", "en", "ate"];
+    /// let t = Twine::concat_strs(&words);
+    /// assert_eq!(t.to_string(), "concatenate");
+    /// assert_eq!(t.len(), 11);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn concat_strs(strs: &'a [&'a str]) -> Twine<'a> {
+        match strs {
+            [] => Twine::empty(),
+            [s] => Twine::from(*s),
+            _ => Twine(TwineKind::Unary(TwineChild::Strs(strs))),
+        }
+    }
+
+    /// Checks if the Twine has 0 childs
+    ///
+    /// # Example
+    /// ```
+    /// # use twine::Twine;
+    /// assert_eq!(Twine::null().is_nullary(), true);
+    /// assert_eq!(Twine::empty().is_nullary(), true);
+    /// assert_eq!(Twine::from("foo").is_nullary(), false);
+    /// ```
+    #[inline(always)]
+    #[must_use]
+    pub fn is_nullary(&self) -> bool {
+        matches!(self.0, TwineKind::Empty | TwineKind::Null)
+    }
+
+    /// Checks if the Twine has 1 childs
+    ///
+    /// # Example
+    /// ```
+    /// # use twine::Twine;
+    /// assert_eq!(Twine::null().is_unary(), false);
+    /// assert_eq!(Twine::empty().is_unary(), false);
+    /// assert_eq!(Twine::from("foo").is_unary(), true);
+    /// assert_eq!(Twine::from(("foo", "bar")).is_unary(), false);
+    /// ```
+    #[inline(always)]
+    #[must_use]
+    pub fn is_unary(&self) -> bool {
+        matches!(self.0, TwineKind::Unary(_))
+    }
+
+    /// Checks if the Twine has 2 childs
+    ///
+    /// # Example
+    /// ```
+    /// # use twine::Twine;
+    /// assert_eq!(Twine::null().is_binary(), false);
+    /// assert_eq!(Twine::empty().is_binary(), false);
+    /// assert_eq!(Twine::from("foo").is_binary(), false);
+    /// assert_eq!(Twine::from(("foo", "bar")).is_binary(), true);
+    /// ```
+    #[inline(always)]
+    #[must_use]
+    pub fn is_binary(&self) -> bool {
+        matches!(self.0, TwineKind::Binary(_, _))
+    }
+
+    /// Checks if the Twine is a null value
+    ///
+    /// # Example
+    /// ```
+    /// # use twine::Twine;
+    /// assert_eq!(Twine::null().is_null(), true);
+    /// assert_eq!(Twine::empty().is_null(), false);
+    /// assert_eq!(Twine::from("foo").is_null(), false);
+    /// assert_eq!(Twine::from(("foo", "bar")).is_null(), false);
+    /// ````
+    #[inline(always)]
+    #[must_use]
+    pub fn is_null(&self) -> bool {
+        matches!(self.0, TwineKind::Null)
+    }
+
+    /// Checks if the Twine is a single, possibly empty, str
+    ///
+    /// # Example
+    /// ```
+    /// # use twine::Twine;
+    /// assert_eq!(Twine::null().is_single_str(), false);
+    /// assert_eq!(Twine::empty().is_single_str(), true);
+    /// assert_eq!(Twine::from("").is_single_str(), true);
+    /// assert_eq!(Twine::from("foo").is_single_str(), true);
+    /// assert_eq!(Twine::from(("foo", "bar")).is_single_str(), false);
+    /// ```
+    #[inline(always)]
+    #[must_use]
+    pub fn is_single_str(&self) -> bool {
+        self.as_single_str().is_some()
+    }
+
+    /// Returns the Twine as a single str if it only contains one str.
+    ///
+    /// # Example
+    /// ```
+    /// # use twine::Twine;
+    /// assert_eq!(Twine::null().as_single_str(), None);
+    /// assert_eq!(Twine::empty().as_single_str(), Some(""));
+    /// assert_eq!(Twine::from("").as_single_str(), Some(""));
+    /// assert_eq!(Twine::from("foo").as_single_str(), Some("foo"));
+    /// assert_eq!(Twine::from(("foo", "bar")).as_single_str(), None);
+    /// ```
+    #[inline(always)]
+    #[must_use]
+    pub fn as_single_str(&self) -> Option<&'a str> {
+        match self.0 {
+            TwineKind::Empty => Some(""),
+            TwineKind::Unary(TwineChild::Str(s)) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// Checks if the Twine is trivially empty because it does not have children.
+    /// Even if false, the Twine still might render to an empty string.
+    ///
+    /// To check if the twine actually renders to an empty string use `is_empty()`
+    ///
+    /// # Example
+    /// ```
+    /// # use twine::Twine;
+    /// assert_eq!(Twine::null().is_trivially_empty(), true);
+    /// assert_eq!(Twine::empty().is_trivially_empty(), true);
+    /// assert_eq!(Twine::from("").is_trivially_empty(), true);
+    /// assert_eq!(Twine::from("foo").is_trivially_empty(), false);
+    /// assert_eq!(Twine::from(("foo", "bar")).is_trivially_empty(), false);
+    /// assert_eq!(Twine::from(("", "")).is_trivially_empty(), true);
+    /// ```
+    #[inline(always)]
+    #[must_use]
+    pub fn is_trivially_empty(&self) -> bool {
+        self.is_nullary()
+    }
+
+    /// Check if the Twine actually renders to an empty string.
+    /// Stops at the first non-empty child.
+    ///
+    /// Strs, chars, integers, floats and closures with a length are checked without rendering them.
+    /// `fmt::Arguments`, `Display` and `Debug` children and closures without a length
+    /// are rendered until they write a non-empty str.
+    /// Joins, paddings and truncations render their content as far as needed.
+    ///
+    /// # Example
+    /// ```
+    /// # use twine::Twine;
+    /// assert_eq!(Twine::null().is_empty(), true);
+    /// assert_eq!(Twine::empty().is_empty(), true);
+    /// assert_eq!(Twine::from("").is_empty(), true);
+    /// assert_eq!(Twine::from("foo").is_empty(), false);
+    /// assert_eq!(Twine::from(("foo", "bar")).is_empty(), false);
+    /// assert_eq!(Twine::from(("", "")).is_empty(), true);
+    /// ```
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.is_empty_within(Twine::MAX_DEPTH)
+    }
+
+    /// Check if the Twine renders to an empty string, using at most `depth` stack entries.
+    fn is_empty_within(&self, depth: usize) -> bool {
+        self.try_for_each_leaf(depth, (), |child| {
+            if child.is_empty(nested_depth(depth)) {
+                Ok(())
+            } else {
+                Err(())
+            }
+        })
+        .is_ok()
+    }
+
+    /// The exact length in bytes of the rendered Twine.
+    ///
+    /// Strs, chars, integers and closures with a length are measured without rendering them,
+    /// repeated Twines are measured once.
+    /// Floats, `fmt::Arguments`, `Display` and `Debug` children and closures without a length
+    /// are rendered to count the bytes they write.
+    /// Joins and truncations measure their content, paddings render it once more to measure its width.
+    ///
+    /// If the Twine is nested deeper than `Twine::MAX_DEPTH`,
+    /// only the part that could be visited is counted.
+    ///
+    /// # Example
+    /// ```
+    /// # use twine::Twine;
+    /// assert_eq!(Twine::null().len(), 0);
+    /// assert_eq!(Twine::empty().len(), 0);
+    /// assert_eq!(Twine::from("foo").len(), 3);
+    /// assert_eq!(Twine::from(("foo", "bar")).len(), 6);
+    /// assert_eq!(Twine::from(&1234u32).len(), 4);
+    /// assert_eq!(Twine::from(&-1234i32).len(), 5);
+    /// assert_eq!(Twine::hex_u64(&0xabc).len(), 3);
+    /// assert_eq!(Twine::from(&'ä').len(), 2);
+    /// let args = format_args!("{}-{}", 1, 2);
+    /// assert_eq!(Twine::from(&args).len(), 3);
+    /// ```
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.len_within(Twine::MAX_DEPTH)
+    }
+
+    /// The length of the part of the Twine that can be visited with at most `depth` stack entries.
+    fn len_within(&self, depth: usize) -> usize {
+        let mut len = 0;
+        let _ = self.try_for_each_leaf(depth, (), |child| {
+            len += child.len(nested_depth(depth));
+            Ok(())
+        });
+        len
+    }
+
+    /// The estimated capacity needed to store the Twine as a String.
+    /// This method returns a vague lower bound needed.
+    /// Floats and `fmt::Arguments` children are estimated without rendering them,
+    /// `Display` and `Debug` children and closures without a length are estimated as empty.
+    /// The items of joins are estimated the same way.
+    /// All other children are measured exactly like `len()`, which renders paddings and truncations.
+    /// Use `len()` to get the exact length.
+    ///
+    /// Use `next_power_of_two()` on the return value to enable efficient allocations
+    /// and reduce re-allocations.
+    ///
+    /// # Example
+    /// ```
+    /// # use twine::Twine;
+    /// assert_eq!(Twine::null().estimated_capacity(), 0);
+    /// assert_eq!(Twine::empty().estimated_capacity(), 0);
+    /// assert_eq!(Twine::from("").estimated_capacity(), 0);
+    /// assert_eq!(Twine::from("foo").estimated_capacity(), 3);
+    /// assert_eq!(Twine::from(("foo", "bar")).estimated_capacity(), 6);
+    /// assert_eq!(Twine::from(("", "")).estimated_capacity(), 0);
+    /// assert_eq!(Twine::from(&42u32).estimated_capacity(), 2);
+    /// ```
+    #[must_use]
+    pub fn estimated_capacity(&self) -> usize {
+        self.estimated_capacity_within(Twine::MAX_DEPTH)
+    }
+
+    /// The estimated capacity of the part of the Twine that can be visited
+    /// with at most `depth` stack entries.
+    fn estimated_capacity_within(&self, depth: usize) -> usize {
+        let mut capacity = 0;
+        let _ = self.try_for_each_leaf(depth, (), |child| {
+            capacity += child.estimated_capacity(nested_depth(depth));
+            Ok(())
+        });
+        capacity
+    }
+
+    /// Render the Twine as a string in the buffer of the writer.
+    ///
+    /// # Errors
+    ///
+    /// This method returns an `fmt::Error` Error Result if writing to `w` fails
+    /// or if the Twine is nested deeper than `Twine::MAX_DEPTH`.
+    ///
+    /// # Example
+    /// ```
+    /// # use twine::Twine;
+    /// let a = &Twine::from("1234");
+    /// let b = &Twine::from(&56u32);
+    /// let c = a.concat(b);
+    /// let mut s = String::new();
+    /// c.write_to(&mut s);
+    /// assert_eq!(s, "123456");
+    /// ```
+    pub fn write_to<W: core::fmt::Write>(&self, w: &mut W) -> core::fmt::Result {
+        self.write_to_within(w, Twine::MAX_DEPTH)
+    }
+
+    /// Render the Twine, failing if it needs more than `depth` stack entries.
+    fn write_to_within<W: core::fmt::Write>(&self, w: &mut W, depth: usize) -> core::fmt::Result {
+        self.try_for_each_leaf(depth, core::fmt::Error, |child| {
+            child.write_to(w, nested_depth(depth))
+        })
+    }
+
+    /// Get the rendered Twine as a str, using `scratch` only if needed.
+    ///
+    /// If the Twine is a single str, it is returned without copying.
+    /// Otherwise `scratch` is cleared and the Twine is rendered into it.
+    ///
+    /// Similar to LLVM's `Twine::toStringRef`.
+    ///
+    /// # Errors
+    ///
+    /// This method returns an `fmt::Error` Error Result if writing to `scratch` fails,
+    /// e.g. if a `StackBuffer` is too small, see `write_to`.
+    ///
+    /// # Example
+    /// ```
+    /// # use twine::{Twine, StackBuffer};
+    /// let mut scratch = StackBuffer::<16>::new();
+    /// let a = &Twine::from("1234");
+    /// assert_eq!(a.to_str_ref(&mut scratch), Ok("1234"));
+    /// assert!(scratch.is_empty());
+    /// let b = &Twine::from(&56u32);
+    /// let c = a.concat(b);
+    /// assert_eq!(c.to_str_ref(&mut scratch), Ok("123456"));
+    /// ```
+    pub fn to_str_ref<'b, B: Buffer>(&self, scratch: &'b mut B) -> Result<&'b str, core::fmt::Error>
+    where
+        'a: 'b,
+    {
+        if let Some(s) = self.as_single_str() {
+            return Ok(s);
+        }
+        scratch.clear();
+        self.write_to(scratch)?;
+        Ok(scratch.as_str())
+    }
+
+    /// Render the Twine as bytes into the `io::Write` writer.
+    ///
+    /// `str` children are written without copying them, one `IoSlice` each,
+    /// using `write_vectored` for consecutive children.
+    /// All other children are rendered into a small buffer on the stack first.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the original `io::Error` Error Result if writing to `w` fails.
+    /// An `io::Error` is also returned if the Twine is nested deeper than `Twine::MAX_DEPTH`.
+    ///
+    /// # Example
+    /// ```
+    /// # use twine::Twine;
+    /// let a = &Twine::from("1234");
+    /// let b = &Twine::from(&56u32);
+    /// let c = a.concat(b);
+    /// let mut v = Vec::new();
+    /// c.write_io(&mut v).unwrap();
+    /// assert_eq!(v, b"123456");
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn write_io<W: std::io::Write>(&self, w: &mut W) -> std::io::Result<()> {
+        io::write_io(self, w)
+    }
+
+    /// Render the Twine into a fixed-size byte buffer and return the rendered str.
+    ///
+    /// This does not require any allocations and is intended for `no_std` targets.
+    ///
+    /// # Errors
+    ///
+    /// This method returns an `Overflow` Error Result if the rendered Twine does not fit into `buf`.
+    /// The error reports the number of bytes needed to render the whole Twine
+    /// and the number of bytes written to the start of `buf`.
+    /// Those bytes are always valid UTF-8, as no code point is ever split.
+    /// Rendering stops at the first str that does not fit,
+    /// and the number of bytes needed is measured with `len` afterwards.
+    ///
+    /// If rendering fails for any other reason (see `write_to`), an `Overflow` is returned as well,
+    /// even though `needed()` might not exceed the length of `buf`.
+    ///
+    /// # Example
+    /// ```
+    /// # use twine::Twine;
+    /// let a = &Twine::from("1234");
+    /// let b = &Twine::from(&56u32);
+    /// let c = a.concat(b);
+    /// let mut buf = [0u8; 8];
+    /// assert_eq!(c.write_to_slice(&mut buf), Ok("123456"));
+    ///
+    /// let d = &Twine::from("äöü");
+    /// let mut buf = [0u8; 5];
+    /// let overflow = d.write_to_slice(&mut buf).unwrap_err();
+    /// assert_eq!(overflow.needed(), 6);
+    /// assert_eq!(overflow.written(), 4);
+    /// assert_eq!(core::str::from_utf8(&buf[..overflow.written()]), Ok("äö"));
+    /// ```
+    pub fn write_to_slice<'b>(&self, buf: &'b mut [u8]) -> Result<&'b str, Overflow> {
+        /// Copies as much as fits into the buffer without splitting a code point
+        /// and fails as soon as a str does not fit.
+        struct SliceWriter<'b> {
+            buf: &'b mut [u8],
+            written: usize,
+        }
+        impl<'b> core::fmt::Write for SliceWriter<'b> {
+            fn write_str(&mut self, s: &str) -> core::fmt::Result {
+                let mut end = s.len().min(self.buf.len() - self.written);
+                while !s.is_char_boundary(end) {
+                    end -= 1;
+                }
+                self.buf[self.written..self.written + end].copy_from_slice(&s.as_bytes()[..end]);
+                self.written += end;
+                if end < s.len() {
+                    // stop rendering the remaining children
+                    return Err(core::fmt::Error);
+                }
+                Ok(())
+            }
+        }
+        let mut w = SliceWriter { buf, written: 0 };
+        if self.write_to(&mut w).is_err() {
+            return Err(Overflow {
+                needed: self.len(),
+                written: w.written,
+            });
+        }
+        let SliceWriter { buf, written } = w;
+        Ok(core::str::from_utf8(&buf[..written]).expect("only whole code points are written"))
+    }
+
+    /// Converts the given Twine to a String
+    ///
+    /// Specialization of the `to_string()` method that pre-allocates the exact capacity
+    /// as returned by `len()`, so the String is allocated exactly once.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if it fails to write to the String it allocates.
+    ///
+    /// # Example
+    /// ```
+    /// # use twine::Twine;
+    /// let a = &Twine::from("1234");
+    /// let b = &Twine::from(&56u32);
     /// let c = a.concat(b);
     /// let s = c.to_string_preallocating();
     /// assert_eq!(s, "123456");
-    /// assert!(s.capacity() >= c.estimated_capacity());
-    /// assert_eq!(s.capacity(), 8);
+    /// assert_eq!(s.capacity(), c.len());
+    /// assert_eq!(s.capacity(), 6);
     /// ```
-    #[cfg(feature = "std")]
+    #[cfg(feature = "alloc")]
     #[must_use]
-    pub fn to_string_preallocating(&self) -> std::string::String {
-        let mut s =
-            std::string::String::with_capacity(self.estimated_capacity().next_power_of_two());
-        // dbg!(s.capacity());
+    pub fn to_string_preallocating(&self) -> alloc::string::String {
+        let mut s = alloc::string::String::with_capacity(self.len());
         self.write_to(&mut s).expect("could not format into String");
-        // dbg!(s.capacity());
         s
     }
+
+    /// Converts the given Twine to a `Cow<str>`
+    ///
+    /// If the Twine is a single str, it is borrowed without copying.
+    /// Otherwise it is rendered into a String using `to_string_preallocating()`.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if it fails to write to the String it allocates.
+    ///
+    /// # Example
+    /// ```
+    /// # use twine::Twine;
+    /// # use std::borrow::Cow;
+    /// let a = &Twine::from("1234");
+    /// assert!(matches!(a.to_cow(), Cow::Borrowed("1234")));
+    /// let b = &Twine::from(&56u32);
+    /// let c = a.concat(b);
+    /// assert!(matches!(c.to_cow(), Cow::Owned(s) if s == "123456"));
+    /// ```
+    #[cfg(feature = "alloc")]
+    #[must_use]
+    pub fn to_cow(&self) -> alloc::borrow::Cow<'a, str> {
+        match self.as_single_str() {
+            Some(s) => alloc::borrow::Cow::Borrowed(s),
+            None => alloc::borrow::Cow::Owned(self.to_string_preallocating()),
+        }
+    }
+
+    /// Converts the given Twine to a null-terminated `CString`
+    ///
+    /// A Twine consisting of a single str that already ends with a NUL byte
+    /// is accepted and not terminated again.
+    ///
+    /// # Errors
+    ///
+    /// This method returns a `NulError` Error Result if the rendered Twine contains a NUL byte.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if it fails to write to the String it allocates.
+    ///
+    /// # Example
+    /// ```
+    /// # use twine::Twine;
+    /// let a = &Twine::from("1234");
+    /// let b = &Twine::from(&56u32);
+    /// let c = a.concat(b);
+    /// assert_eq!(c.to_cstring().unwrap().as_bytes(), b"123456");
+    /// assert_eq!(Twine::from("foo\0").to_cstring().unwrap().as_bytes(), b"foo");
+    /// assert!(Twine::from("f\0o").to_cstring().is_err());
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn to_cstring(&self) -> Result<alloc::ffi::CString, alloc::ffi::NulError> {
+        if let Some(s) = self.as_single_str() {
+            return alloc::ffi::CString::new(s.strip_suffix('\0').unwrap_or(s));
+        }
+        // reserve the space for the NUL byte, so `CString::new` does not reallocate
+        let mut s = alloc::string::String::with_capacity(self.len() + 1);
+        self.write_to(&mut s).expect("could not format into String");
+        alloc::ffi::CString::new(s)
+    }
+
+    /// Call `f` with the Twine rendered as a null-terminated `CStr`.
+    ///
+    /// The Twine is rendered into a buffer on the stack,
+    /// only if it does not fit there, it is rendered into a `CString` on the heap.
+    /// A Twine consisting of a single str that already ends with a NUL byte
+    /// is passed to `f` without copying.
+    ///
+    /// Similar to LLVM's `Twine::toNullTerminatedStringRef`.
+    ///
+    /// # Errors
+    ///
+    /// This method returns a `NulError` Error Result if the rendered Twine contains a NUL byte.
+    /// `f` is not called in that case.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the Twine is too large for the stack buffer
+    /// and it fails to write to the String it allocates.
+    ///
+    /// # Example
+    /// ```
+    /// # use twine::Twine;
+    /// let a = &Twine::from("1234");
+    /// let b = &Twine::from(&56u32);
+    /// let c = a.concat(b);
+    /// assert_eq!(c.with_c_str(|s| s.to_bytes().len()), Ok(6));
+    /// assert_eq!(Twine::from("foo\0").with_c_str(|s| s.as_ptr() as usize), Ok("foo\0".as_ptr() as usize));
+    /// assert!(Twine::from("f\0o").with_c_str(|_| ()).is_err());
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn with_c_str<R>(
+        &self,
+        f: impl FnOnce(&core::ffi::CStr) -> R,
+    ) -> Result<R, alloc::ffi::NulError> {
+        /// Size of the stack buffer used for rendering, including the NUL byte
+        const STACK_LEN: usize = 256;
+
+        if let Some(s) = self.as_single_str() {
+            if let Ok(c_str) = core::ffi::CStr::from_bytes_with_nul(s.as_bytes()) {
+                return Ok(f(c_str));
+            }
+        }
+        let mut buf = StackBuffer::<STACK_LEN>::new();
+        if self.write_to(&mut buf).is_ok() && core::fmt::Write::write_char(&mut buf, '\0').is_ok() {
+            let bytes = buf.as_bytes();
+            return match core::ffi::CStr::from_bytes_with_nul(bytes) {
+                Ok(c_str) => Ok(f(c_str)),
+                // let CString report the position of the interior NUL byte
+                Err(_) => Err(alloc::ffi::CString::new(&bytes[..bytes.len() - 1])
+                    .expect_err("bytes contain an interior NUL byte")),
+            };
+        }
+        Ok(f(&self.to_cstring()?))
+    }
+}
+
+/// Error returned by `Twine::write_to_slice` if the buffer is too small to render the Twine.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Overflow {
+    needed: usize,
+    written: usize,
+}
+
+impl Overflow {
+    /// The number of bytes needed to render the whole Twine.
+    #[inline(always)]
+    #[must_use]
+    pub fn needed(&self) -> usize {
+        self.needed
+    }
+
+    /// The number of bytes written to the start of the buffer.
+    ///
+    /// These bytes are always valid UTF-8.
+    #[inline(always)]
+    #[must_use]
+    pub fn written(&self) -> usize {
+        self.written
+    }
 }
 
+impl core::fmt::Display for Overflow {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "buffer too small to render Twine: {} bytes needed, {} bytes written",
+            self.needed, self.written
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Overflow {}
+
 impl<'a> core::fmt::Display for Twine<'a> {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         self.write_to(f)
@@ -532,45 +1658,214 @@ impl<'a> core::ops::Add<&'a Twine<'a>> for &'a Twine<'a> {
     }
 }
 
+#[cfg(feature = "alloc")]
+impl From<&Twine<'_>> for alloc::string::String {
+    /// Render the Twine into a String, see `Twine::to_string_preallocating`.
+    #[inline]
+    fn from(t: &Twine<'_>) -> Self {
+        t.to_string_preallocating()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl From<&Twine<'_>> for alloc::boxed::Box<str> {
+    /// Render the Twine into a boxed str.
+    #[inline]
+    fn from(t: &Twine<'_>) -> Self {
+        t.to_string_preallocating().into_boxed_str()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a> From<&Twine<'a>> for alloc::borrow::Cow<'a, str> {
+    /// Borrow or render the Twine, see `Twine::to_cow`.
+    #[inline]
+    fn from(t: &Twine<'a>) -> Self {
+        t.to_cow()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl From<&Twine<'_>> for alloc::rc::Rc<str> {
+    /// Render the Twine into a reference counted str.
+    ///
+    /// A Twine consisting of a single str is copied without rendering it into a String first.
+    #[inline]
+    fn from(t: &Twine<'_>) -> Self {
+        match t.as_single_str() {
+            Some(s) => alloc::rc::Rc::from(s),
+            None => alloc::rc::Rc::from(t.to_string_preallocating()),
+        }
+    }
+}
+
+#[cfg(all(feature = "alloc", target_has_atomic = "ptr"))]
+impl From<&Twine<'_>> for alloc::sync::Arc<str> {
+    /// Render the Twine into an atomically reference counted str.
+    ///
+    /// A Twine consisting of a single str is copied without rendering it into a String first.
+    #[inline]
+    fn from(t: &Twine<'_>) -> Self {
+        match t.as_single_str() {
+            Some(s) => alloc::sync::Arc::from(s),
+            None => alloc::sync::Arc::from(t.to_string_preallocating()),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<&Twine<'_>> for std::ffi::OsString {
+    /// Render the Twine into an OsString.
+    #[inline]
+    fn from(t: &Twine<'_>) -> Self {
+        std::ffi::OsString::from(t.to_string_preallocating())
+    }
+}
+
+/// The methods of leaf children get the number of stack entries left for the Twines nested in them.
 impl<'a> TwineChild<'a> {
-    fn estimated_capacity(&self) -> usize {
+    fn is_empty(&self, depth: usize) -> bool {
+        /// Fails on the first non-empty write
+        struct EmptyWriter;
+        impl core::fmt::Write for EmptyWriter {
+            fn write_str(&mut self, s: &str) -> core::fmt::Result {
+                if s.is_empty() {
+                    Ok(())
+                } else {
+                    Err(core::fmt::Error)
+                }
+            }
+        }
         match self {
-            TwineChild::Twine(t) => t.estimated_capacity(),
+            TwineChild::Twine(t) => t.is_empty_within(depth),
+            TwineChild::Str(string) => string.is_empty(),
+            TwineChild::FmtArgs(f) => f.as_str().map_or_else(
+                || core::fmt::Write::write_fmt(&mut EmptyWriter, **f).is_ok(),
+                str::is_empty,
+            ),
+            TwineChild::Display(d) => {
+                core::fmt::Write::write_fmt(&mut EmptyWriter, format_args!("{}", d.0)).is_ok()
+            }
+            TwineChild::Debug(d) => {
+                core::fmt::Write::write_fmt(&mut EmptyWriter, format_args!("{:?}", d.0)).is_ok()
+            }
+            TwineChild::Fn(f) => {
+                f.0.len_hint()
+                    .map_or_else(|| f.0.write_to(&mut EmptyWriter).is_ok(), |len| len == 0)
+            }
+            TwineChild::Join(join) => join.0.is_empty(depth),
+            TwineChild::Pad(pad) => pad.is_empty(depth),
+            TwineChild::Truncate(truncate) => truncate.is_empty(depth),
+            TwineChild::Strs(strs) => strs.iter().all(|s| s.is_empty()),
+            TwineChild::Slice(ts) => ts.iter().all(|t| t.is_empty_within(depth)),
+            TwineChild::Repeat(t, _) => t.is_empty_within(depth),
+            _ => false,
+        }
+    }
+
+    fn estimated_capacity(&self, depth: usize) -> usize {
+        match self {
+            TwineChild::Twine(t) => t.estimated_capacity_within(depth),
             TwineChild::Str(string) => string.len(),
             TwineChild::Char(ch) => ch.len_utf8(),
-            TwineChild::DecUsize(_)
-            | TwineChild::DecU64(_)
-            | TwineChild::DecU32(_)
-            | TwineChild::DecU16(_)
-            | TwineChild::DecIsize(_)
-            | TwineChild::DecI64(_)
-            | TwineChild::DecI32(_)
-            | TwineChild::DecI16(_)
-            | TwineChild::HexU64(_)
-            | TwineChild::HexUsize(_) => 2,
             // NOTE: all fields of fmt::Arguments are private
             // and all methods are marked as fmt_internal
             // so we can not actually access any better information
             TwineChild::FmtArgs(a) => a.as_str().map_or(1, str::len),
+            TwineChild::Float(x, format) => x.estimated_len(*format),
+            TwineChild::Display(_) | TwineChild::Debug(_) => 0,
+            TwineChild::Fn(f) => f.0.len_hint().unwrap_or(0),
+            TwineChild::Join(join) => join.0.estimated_capacity(depth),
+            #[cfg(feature = "alloc")]
+            TwineChild::Buf(buf) => buf.estimated_capacity(),
+            TwineChild::Slice(ts) => ts.iter().map(|t| t.estimated_capacity_within(depth)).sum(),
+            TwineChild::Repeat(t, count) => {
+                t.estimated_capacity_within(depth).saturating_mul(*count)
+            }
+            _ => self.len(depth),
         }
     }
 
-    fn write_to<W: core::fmt::Write>(&self, w: &mut W) -> core::fmt::Result {
+    fn len(&self, depth: usize) -> usize {
         match self {
-            TwineChild::Twine(t) => t.write_to(w),
+            TwineChild::Twine(t) => t.len_within(depth),
+            TwineChild::Str(string) => string.len(),
+            TwineChild::Char(ch) => ch.len_utf8(),
+            TwineChild::Dec(x) => {
+                let (abs, negative) = x.unsigned_abs();
+                digits::dec_len(abs) + usize::from(negative)
+            }
+            TwineChild::Radix(x, radix, format) => {
+                let (n, negative) = x.radix_digits(*radix);
+                digits::radix_len(n, negative, *radix, *format)
+            }
+            TwineChild::Float(x, format) => {
+                let mut w = WriteCounter(0);
+                let _ = x.write_to(&mut w, *format);
+                w.0
+            }
+            #[cfg(feature = "alloc")]
+            TwineChild::Buf(buf) => buf.len(),
+            TwineChild::FmtArgs(f) => f.as_str().map_or_else(
+                || {
+                    let mut w = WriteCounter(0);
+                    let _ = core::fmt::Write::write_fmt(&mut w, **f);
+                    w.0
+                },
+                str::len,
+            ),
+            TwineChild::Display(d) => {
+                let mut w = WriteCounter(0);
+                let _ = core::fmt::Write::write_fmt(&mut w, format_args!("{}", d.0));
+                w.0
+            }
+            TwineChild::Debug(d) => {
+                let mut w = WriteCounter(0);
+                let _ = core::fmt::Write::write_fmt(&mut w, format_args!("{:?}", d.0));
+                w.0
+            }
+            TwineChild::Fn(f) => f.0.len_hint().unwrap_or_else(|| {
+                let mut w = WriteCounter(0);
+                let _ = f.0.write_to(&mut w);
+                w.0
+            }),
+            TwineChild::Join(join) => join.0.len(depth),
+            TwineChild::Pad(pad) => pad.len(depth),
+            TwineChild::Truncate(truncate) => truncate.len(depth),
+            TwineChild::Strs(strs) => strs.iter().map(|s| s.len()).sum(),
+            TwineChild::Slice(ts) => ts.iter().map(|t| t.len_within(depth)).sum(),
+            TwineChild::Repeat(t, count) => t.len_within(depth).saturating_mul(*count),
+        }
+    }
+
+    fn write_to<W: core::fmt::Write>(&self, w: &mut W, depth: usize) -> core::fmt::Result {
+        match self {
+            TwineChild::Twine(t) => t.write_to_within(w, depth),
             TwineChild::Str(string) => w.write_str(string),
             TwineChild::Char(ch) => w.write_char(**ch),
-            TwineChild::DecUsize(x) => write!(w, "{x}"),
-            TwineChild::DecU64(x) => write!(w, "{x}"),
-            TwineChild::DecU32(x) => write!(w, "{x}"),
-            TwineChild::DecU16(x) => write!(w, "{x}"),
-            TwineChild::DecIsize(x) => write!(w, "{x}"),
-            TwineChild::DecI64(x) => write!(w, "{x}"),
-            TwineChild::DecI32(x) => write!(w, "{x}"),
-            TwineChild::DecI16(x) => write!(w, "{x}"),
-            TwineChild::HexU64(x) => write!(w, "{x:x}"),
-            TwineChild::HexUsize(x) => write!(w, "{x:x}"),
+            TwineChild::Dec(x) => {
+                let (abs, negative) = x.unsigned_abs();
+                digits::write_dec(w, abs, negative)
+            }
+            TwineChild::Radix(x, radix, format) => {
+                let (n, negative) = x.radix_digits(*radix);
+                digits::write_radix(w, n, negative, *radix, *format)
+            }
+            TwineChild::Float(x, format) => x.write_to(w, *format),
             TwineChild::FmtArgs(f) => w.write_fmt(**f),
+            TwineChild::Display(d) => w.write_fmt(format_args!("{}", d.0)),
+            TwineChild::Debug(d) => w.write_fmt(format_args!("{:?}", d.0)),
+            TwineChild::Fn(f) => f.0.write_to(w),
+            TwineChild::Join(join) => join.0.write_to(w, depth),
+            TwineChild::Pad(pad) => pad.write_to(w, depth),
+            TwineChild::Truncate(truncate) => truncate.render(w, depth).map(|_| ()),
+            TwineChild::Strs(strs) => strs.iter().try_for_each(|s| w.write_str(s)),
+            TwineChild::Slice(ts) => ts.iter().try_for_each(|t| t.write_to_within(w, depth)),
+            TwineChild::Repeat(t, count) => {
+                (0..*count).try_for_each(|_| t.write_to_within(w, depth))
+            }
+            #[cfg(feature = "alloc")]
+            TwineChild::Buf(buf) => buf.try_for_each_leaf(|child| child.write_to(w, depth)),
         }
     }
 }
@@ -604,4 +1899,590 @@ mod test {
         assert_eq!(s1, "bumpalloc-1");
         assert!(s1.capacity() >= 11);
     }
+
+    #[test]
+    fn deep_left_chain() {
+        const DEPTH: usize = 1_000_000;
+        let bump = bumpalo::Bump::new();
+        let piece = &Twine::from("b");
+        let mut t: &Twine = bump.alloc(Twine::from("a"));
+        for _ in 0..DEPTH {
+            t = bump.alloc(t + piece);
+        }
+        let mut s = std::string::String::new();
+        t.write_to(&mut s).unwrap();
+        assert_eq!(s.len(), DEPTH + 1);
+        assert!(s.starts_with("ab"));
+        assert!(s.bytes().skip(1).all(|b| b == b'b'));
+        assert_eq!(t.estimated_capacity(), DEPTH + 1);
+        assert!(!t.is_empty());
+    }
+
+    #[test]
+    fn deep_right_chain() {
+        const DEPTH: usize = 1_000_000;
+        let bump = bumpalo::Bump::new();
+        let piece = &Twine::from("b");
+        let mut t: &Twine = bump.alloc(Twine::from("a"));
+        for _ in 0..DEPTH {
+            t = bump.alloc(piece + t);
+        }
+        let mut s = std::string::String::new();
+        t.write_to(&mut s).unwrap();
+        assert_eq!(s.len(), DEPTH + 1);
+        assert!(s.ends_with("ba"));
+        assert!(s.bytes().rev().skip(1).all(|b| b == b'b'));
+        assert_eq!(t.estimated_capacity(), DEPTH + 1);
+        assert!(!t.is_empty());
+    }
+
+    #[test]
+    fn deep_empty_chain() {
+        const DEPTH: usize = 1_000_000;
+        let bump = bumpalo::Bump::new();
+        let args = format_args!("");
+        let piece = &Twine::from(&args);
+        let mut t: &Twine = piece;
+        for _ in 0..DEPTH {
+            t = bump.alloc(t + piece);
+        }
+        assert!(t.is_empty());
+        assert_eq!(t.estimated_capacity(), 0);
+    }
+
+    #[test]
+    fn mixed_nesting() {
+        let a = &Twine::from("a");
+        let b = &Twine::from(&1u32);
+        let c = &Twine::from(&'c');
+        let ab = &(a + b);
+        let bc = &(b + c);
+        let abbc = &(ab + bc);
+        let cab = &(c + ab);
+        let t = &(abbc + cab);
+        assert_eq!(std::format!("{t}"), "a11cca1");
+        let u = &(cab + abbc);
+        assert_eq!(std::format!("{u}"), "ca1a11c");
+        let v = &(u + t);
+        assert_eq!(std::format!("{v}"), "ca1a11ca11cca1");
+    }
+
+    #[test]
+    fn max_depth_exceeded() {
+        let bump = bumpalo::Bump::new();
+        let piece = &Twine::from("b");
+        let mut t: &Twine = bump.alloc(Twine::from("a"));
+        for _ in 0..2 * Twine::MAX_DEPTH {
+            t = bump.alloc(t + piece);
+            t = bump.alloc(piece + t);
+        }
+        let mut s = std::string::String::new();
+        assert!(t.write_to(&mut s).is_err());
+        assert!(!t.is_empty());
+    }
+
+    #[test]
+    fn integer_len() {
+        use std::string::ToString;
+        for x in [0u64, 1, 9, 10, 99, 100, 12345, u64::MAX / 10, u64::MAX] {
+            assert_eq!(Twine::from(&x).len(), x.to_string().len());
+            assert_eq!(Twine::hex_u64(&x).len(), std::format!("{x:x}").len());
+        }
+        for x in [0i64, -1, 9, -10, 99, -100, i64::MIN, i64::MAX] {
+            assert_eq!(Twine::from(&x).len(), x.to_string().len());
+        }
+        for x in [0i16, -1, i16::MIN, i16::MAX] {
+            assert_eq!(Twine::from(&x).len(), x.to_string().len());
+        }
+        for x in [0usize, 15, 16, usize::MAX] {
+            assert_eq!(Twine::hex_usize(&x).len(), std::format!("{x:x}").len());
+        }
+    }
+
+    #[test]
+    fn write_to_slice() {
+        let a = &Twine::from("ab");
+        let b = &Twine::from(&'€');
+        let c = &Twine::from(&-12i32);
+        let ab = &(a + b);
+        let t = &(ab + c);
+        let mut buf = [0u8; 8];
+        assert_eq!(t.write_to_slice(&mut buf), Ok("ab€-12"));
+        for len in 0..8 {
+            let mut buf = [0u8; 8];
+            let overflow = t.write_to_slice(&mut buf[..len]).unwrap_err();
+            assert_eq!(overflow.needed(), 8);
+            let expected = match len {
+                2..=4 => 2,
+                _ => len,
+            };
+            assert_eq!(overflow.written(), expected);
+            assert!(core::str::from_utf8(&buf[..overflow.written()]).is_ok());
+        }
+        let mut buf = [];
+        assert_eq!(Twine::empty().write_to_slice(&mut buf), Ok(""));
+
+        // the children after the overflow are only rendered to measure them
+        let calls = core::cell::Cell::new(0);
+        let render = |w: &mut dyn core::fmt::Write| {
+            calls.set(calls.get() + 1);
+            w.write_str("lazy")
+        };
+        let lazy = &Twine::from_fn(&render);
+        let t = &(t + lazy);
+        let mut buf = [0u8; 4];
+        let overflow = t.write_to_slice(&mut buf).unwrap_err();
+        assert_eq!(overflow.needed(), 12);
+        assert_eq!(overflow.written(), 2);
+        assert_eq!(calls.get(), 1);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn with_c_str_heap_fallback() {
+        let long = "x".repeat(1000);
+        let a = &Twine::from(&long);
+        let b = &Twine::from(&42u32);
+        let t = &(a + b);
+        let len = t.with_c_str(|s| s.to_bytes().len()).unwrap();
+        assert_eq!(len, 1002);
+        let nul = &Twine::from("\0");
+        let with_nul = t + nul;
+        let err = with_nul.with_c_str(|_| ()).unwrap_err();
+        assert_eq!(err.nul_position(), 1002);
+        let short_with_nul = b + nul;
+        let err = short_with_nul.with_c_str(|_| ()).unwrap_err();
+        assert_eq!(err.nul_position(), 2);
+    }
+
+    #[test]
+    fn all_integer_widths() {
+        use core::num::{NonZeroI128, NonZeroI8, NonZeroU128, NonZeroU8, NonZeroUsize};
+        use std::string::ToString;
+
+        macro_rules! check {
+            ($($x:expr),* $(,)?) => {
+                $(
+                    let x = $x;
+                    assert_eq!(Twine::from(&x).to_string(), std::format!("{x}"));
+                    assert_eq!(Twine::from(&x).len(), std::format!("{x}").len());
+                    assert_eq!(Twine::hex(&x).to_string(), std::format!("{x:x}"));
+                    assert_eq!(Twine::hex(&x).len(), std::format!("{x:x}").len());
+                )*
+            };
+        }
+        check!(0u8, u8::MAX, 0i8, i8::MIN, i8::MAX, -1i8);
+        check!(u16::MAX, i16::MIN, i16::MAX, u32::MAX, i32::MIN, i32::MAX);
+        check!(
+            u64::MAX,
+            i64::MIN,
+            i64::MAX,
+            usize::MAX,
+            isize::MIN,
+            isize::MAX
+        );
+        check!(0u128, u128::MAX, i128::MIN, i128::MAX, -1i128);
+        check!(NonZeroU8::MAX, NonZeroI8::MIN, NonZeroUsize::MAX);
+        check!(
+            NonZeroU128::MAX,
+            NonZeroI128::MIN,
+            NonZeroI128::new(-42).unwrap()
+        );
+        assert_eq!(Twine::from(&1u8).to_string(), 1u8.to_string());
+    }
+
+    #[test]
+    fn float_len() {
+        use std::string::ToString;
+        for x in [
+            0.0,
+            -0.0,
+            0.1,
+            -1.5,
+            1e20,
+            1e-20,
+            f64::MAX,
+            f64::NAN,
+            f64::NEG_INFINITY,
+        ] {
+            let t = Twine::from(&x);
+            assert_eq!(t.len(), x.to_string().len());
+            assert!(t.estimated_capacity() <= t.len());
+            let t = Twine::float_fixed(&x, 4);
+            assert_eq!(t.len(), std::format!("{x:.4}").len());
+            assert!(t.estimated_capacity() <= t.len());
+            let t = Twine::float_exp(&x);
+            assert_eq!(t.len(), std::format!("{x:e}").len());
+            assert!(t.estimated_capacity() <= t.len());
+            assert!(!t.is_empty());
+        }
+    }
+
+    #[test]
+    fn hex_format() {
+        use std::string::ToString;
+
+        let format = IntFormat::new();
+        for x in [0u64, 0xa, 0xabcd, 0x1234_5678_9abc, u64::MAX] {
+            let zero_pad = format.width(10).zero_pad(true).prefix(true);
+            assert_eq!(
+                Twine::hex_with(&x, zero_pad).to_string(),
+                std::format!("{x:#012x}")
+            );
+            let upper = format.width(10).uppercase(true);
+            assert_eq!(
+                Twine::hex_with(&x, upper).to_string(),
+                std::format!("{x:10X}")
+            );
+            let aligned = format.width(10).prefix(true);
+            assert_eq!(
+                Twine::hex_with(&x, aligned).to_string(),
+                std::format!("{x:#12x}")
+            );
+        }
+
+        let cases = [
+            (
+                0x7fff,
+                format.width(8).zero_pad(true).separator('_', 4),
+                "0000_7fff",
+            ),
+            (0x7fff, format.width(8).separator('_', 4), "     7fff"),
+            (0x7fff, format.width(9).separator('_', 4), "       7fff"),
+            (0x7ffff, format.width(8).separator('_', 4), "   7_ffff"),
+            (
+                0x7ffff,
+                format.width(8).prefix(true).separator('_', 4),
+                "   0x7_ffff",
+            ),
+            (0xabcdef, format.separator('\'', 2), "ab'cd'ef"),
+            (0xabcdef, format.separator('·', 3).prefix(true), "0xabc·def"),
+            (
+                0xabcdef,
+                format.width(8).zero_pad(true).separator('·', 3),
+                "00·abc·def",
+            ),
+            (0xabcdef, format.width(8).separator('·', 3), "   abc·def"),
+            (0, format.separator('_', 1), "0"),
+            (0x123, format.separator('_', 0), "123"),
+        ];
+        for (x, format, expected) in cases {
+            let t = Twine::hex_with(&x, format);
+            assert_eq!(t.to_string(), expected);
+            assert_eq!(t.len(), expected.len(), "{expected}");
+            assert_eq!(t.estimated_capacity(), expected.len(), "{expected}");
+        }
+
+        let long = format.width(200).zero_pad(true).separator('→', 1);
+        let t = Twine::hex_with(&u128::MAX, long);
+        assert_eq!(t.len(), t.to_string().len());
+    }
+
+    #[test]
+    fn radix_format() {
+        use std::string::ToString;
+
+        let format = IntFormat::new();
+        for x in [0u64, 0o7, 0o755, 0b1011_0110, u64::MAX] {
+            assert_eq!(
+                Twine::octal_with(&x, format.width(8).zero_pad(true).prefix(true)).to_string(),
+                std::format!("{x:#010o}")
+            );
+            assert_eq!(
+                Twine::binary_with(&x, format.width(16).prefix(true)).to_string(),
+                std::format!("{x:#18b}")
+            );
+        }
+
+        let ones = ["11111111"; 16].join("_");
+        let cases = [
+            (Twine::octal_with(&0o755u16, format.prefix(true)), "0o755"),
+            (
+                Twine::binary_with(&5u8, format.width(8).zero_pad(true).separator('_', 4)),
+                "0000_0101",
+            ),
+            (
+                Twine::binary_with(&5u8, format.width(8).separator('_', 4)),
+                "      101",
+            ),
+            (
+                Twine::radix_with(&35u8, 36, format.uppercase(true).prefix(true)),
+                "Z",
+            ),
+            (
+                Twine::radix_with(&1_000_000u32, 10, format.separator(',', 3)),
+                "1,000,000",
+            ),
+            (Twine::radix_with(&-1i8, 10, format), "-1"),
+            (
+                Twine::radix_with(&i128::MIN, 10, format),
+                "-170141183460469231731687303715884105728",
+            ),
+            (
+                Twine::radix_with(
+                    &-1295i32,
+                    36,
+                    format.width(4).zero_pad(true).uppercase(true),
+                ),
+                "-00ZZ",
+            ),
+            (
+                Twine::radix_with(&-35i64, 36, format.width(3).separator(' ', 2)),
+                "   -z",
+            ),
+            (Twine::radix_with(&-1i8, 16, format), "ff"),
+            (
+                Twine::binary_with(&u128::MAX, format.separator('_', 8)),
+                &*ones,
+            ),
+        ];
+        for (t, expected) in cases {
+            assert_eq!(t.to_string(), expected);
+            assert_eq!(t.len(), expected.len(), "{expected}");
+            assert_eq!(t.estimated_capacity(), expected.len(), "{expected}");
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "radix must be in the range from 2 to 36")]
+    fn radix_out_of_range() {
+        let _ = Twine::radix(&1u8, 37);
+    }
+
+    #[test]
+    fn twine_macro() {
+        use std::string::ToString;
+
+        fn render(t: &Twine) -> std::string::String {
+            t.to_string()
+        }
+
+        let s = "owned";
+        let x = 7i64;
+        let args = format_args!("<{}>", x);
+        let inner = Twine::from("inner");
+        assert_eq!(render(&twine!()), "");
+        assert_eq!(render(&twine!("single")), "single");
+        assert_eq!(
+            render(&twine!(s, '-', x * 2, "-", 0.5f32, args, inner, &inner,)),
+            "owned-14-0.5<7>innerinner"
+        );
+
+        twine!(let empty =);
+        assert!(empty.is_empty());
+        twine!(let many = 0u8, 1u16, 2u32, 3u64, 4u128, 5usize, 6i8, 7i16, 8i32, 9i64);
+        twine!(let nested = many, many, x - 7, many);
+        assert_eq!(nested.to_string(), "0123456789012345678900123456789");
+        assert_eq!(nested.len(), 31);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn alloc_conversions() {
+        use alloc::borrow::Cow;
+        use alloc::boxed::Box;
+        use alloc::rc::Rc;
+        use alloc::string::{String, ToString};
+        use alloc::sync::Arc;
+
+        let n = 42u8;
+        let single = Twine::from("single");
+        let concat = Twine::from(("con", "cat"));
+        let number = Twine::from(&n);
+        for (t, expected) in [(single, "single"), (concat, "concat"), (number, "42")] {
+            assert_eq!(String::from(&t), expected);
+            assert_eq!(&*Box::<str>::from(&t), expected);
+            assert_eq!(Cow::from(&t), expected);
+            assert_eq!(&*Rc::<str>::from(&t), expected);
+            assert_eq!(&*Arc::<str>::from(&t), expected);
+            #[cfg(feature = "std")]
+            assert_eq!(std::ffi::OsString::from(&t), expected);
+        }
+
+        let boxed: Box<str> = Box::from("box");
+        let cow = Cow::Borrowed("cow");
+        let rc: Rc<str> = Rc::from("rc");
+        let arc: Arc<str> = Arc::from("arc");
+        assert_eq!(
+            twine!(
+                Twine::from(&boxed),
+                Twine::from(&cow),
+                Twine::from(&rc),
+                Twine::from(&arc)
+            )
+            .to_string(),
+            "boxcowrcarc"
+        );
+        let string = String::from("string");
+        assert_eq!(
+            twine!(string, boxed, cow, rc, arc).to_string(),
+            "stringboxcowrcarc"
+        );
+    }
+
+    #[test]
+    fn concat_slices() {
+        use std::string::ToString;
+        use std::vec::Vec;
+
+        let words: Vec<&str> = (0..1000).map(|i| ["a", "bc", "", "d"][i % 4]).collect();
+        let strs = Twine::concat_strs(&words);
+        assert_eq!(strs.len(), 1000);
+        assert_eq!(strs.to_string(), words.concat());
+
+        let numbers: Vec<u32> = (0..1000).collect();
+        let twines: Vec<Twine> = numbers.iter().map(Twine::from).collect();
+        let all = Twine::concat_all(&twines);
+        let expected: std::string::String = numbers.iter().map(u32::to_string).collect();
+        assert_eq!(all.to_string(), expected);
+        assert_eq!(all.len(), expected.len());
+
+        // slices nested in slices and binary nodes on both sides of a slice
+        let view = ConcatDisplay::new(&numbers[..3]);
+        let display = Twine::from(&view);
+        let inner = [Twine::from("<"), all, strs, display, Twine::from(">")];
+        let inner = Twine::concat_all(&inner);
+        let prefix = Twine::from(&'[');
+        let suffix = Twine::from(&']');
+        let left = &prefix + &inner;
+        let right = &left + &suffix;
+        let outer = [right, Twine::empty(), right];
+        let outer = Twine::concat_all(&outer);
+        let once = std::format!("[<{expected}{}012>]", words.concat());
+        assert_eq!(outer.to_string(), std::format!("{once}{once}"));
+        assert_eq!(outer.len(), 2 * once.len());
+        #[cfg(feature = "std")]
+        {
+            let mut bytes = Vec::new();
+            outer.write_io(&mut bytes).unwrap();
+            assert_eq!(bytes, std::format!("{once}{once}").as_bytes());
+        }
+
+        let empty: [Twine; 3] = [Twine::empty(), Twine::from(""), Twine::from(("", ""))];
+        assert!(Twine::concat_all(&empty).is_empty());
+        let empty_view = ConcatDisplay::new(&numbers[..0]);
+        assert!(Twine::from(&empty_view).is_empty());
+        assert!(Twine::concat_strs(&["", ""]).is_empty());
+    }
+
+    #[test]
+    fn deep_slice_nesting() {
+        let bump = bumpalo::Bump::new();
+        let mut t: &Twine = bump.alloc(Twine::from("x"));
+        for _ in 0..Twine::MAX_DEPTH {
+            let slice = bump.alloc([*t, Twine::from("y")]);
+            t = bump.alloc(Twine::concat_all(slice));
+        }
+        let mut s = std::string::String::new();
+        t.write_to(&mut s).unwrap();
+        assert_eq!(s.len(), Twine::MAX_DEPTH + 1);
+        let slice = bump.alloc([*t, Twine::from("y")]);
+        let too_deep = Twine::concat_all(slice);
+        assert!(too_deep.write_to(&mut s).is_err());
+    }
+
+    #[test]
+    fn repeat() {
+        use std::string::ToString;
+        let indent = Twine::from("  ");
+        let n = 12u8;
+        let label = Twine::from(("item ", ""));
+        let hex = Twine::hex(&n);
+        let item = label.concat(&hex);
+        let indentation = Twine::repeat(&indent, 2);
+        let indented = indentation.concat(&item);
+        let block = Twine::repeat(&indented, 3);
+        assert_eq!(block.to_string(), "    item c    item c    item c");
+        assert_eq!(block.len(), 30);
+        assert_eq!(block.estimated_capacity(), 30);
+        #[cfg(feature = "std")]
+        {
+            let mut bytes = std::vec::Vec::new();
+            block.write_io(&mut bytes).unwrap();
+            assert_eq!(bytes, b"    item c    item c    item c");
+        }
+
+        let empty = Twine::from("");
+        assert!(Twine::repeat(&empty, 5).is_empty());
+        assert!(Twine::repeat(&Twine::null(), 5).is_null());
+        assert!(Twine::repeat(&Twine::null(), 0).is_null());
+        assert!(Twine::repeat(&item, 1).is_binary());
+        let dash = '-';
+        let rule = Twine::from(&dash);
+        let huge = Twine::repeat(&rule, usize::MAX);
+        assert_eq!(huge.len(), usize::MAX);
+        assert!(!huge.is_empty());
+    }
+
+    #[test]
+    fn display_and_debug() {
+        use std::string::ToString;
+        let ip = core::net::IpAddr::from([127, 0, 0, 1]);
+        let port = 8080u16;
+        let elapsed = core::time::Duration::from_millis(1500);
+        let missing: Option<u8> = None;
+        crate::twine!(
+            let t = Twine::from(&ip),
+            ':',
+            port,
+            " ",
+            Twine::from(&elapsed),
+            " ",
+            Twine::debug(&missing),
+            Twine::display(&""),
+        );
+        let expected = "127.0.0.1:8080 1.5s None";
+        assert_eq!(t.to_string(), expected);
+        assert_eq!(t.len(), expected.len());
+        assert!(Twine::display(&"").is_empty());
+        assert!(!Twine::debug(&"").is_empty());
+        #[cfg(feature = "alloc")]
+        {
+            let copy = crate::TwineBuf::from(&t);
+            assert_eq!(copy.to_string(), expected);
+        }
+        #[cfg(feature = "std")]
+        {
+            let path = std::path::Path::new("/tmp/log");
+            let path = path.display();
+            assert_eq!(Twine::from(&path).to_string(), "/tmp/log");
+        }
+        assert_eq!(
+            std::format!("{:?}", Twine::debug(&'x')),
+            "Twine(Unary(Debug(DynDebug('x'))))"
+        );
+    }
+
+    #[test]
+    fn bools_and_options() {
+        use std::string::ToString;
+        let flags = [true, false];
+        let id = Some(-3i8);
+        let name: Option<&str> = None;
+        let nested = Some(Twine::from("nested"));
+        crate::twine!(
+            let t = flags[0],
+            ' ',
+            Twine::bool_with(&flags[1], "on", Twine::from(("o", "ff"))),
+            ' ',
+            Twine::optional(&id, "?"),
+            ' ',
+            Twine::optional(&name, Twine::null()),
+            Twine::optional(&nested, "?"),
+        );
+        assert!(t.is_null());
+        crate::twine!(
+            let t = flags[1],
+            ' ',
+            Twine::bool_with(&flags[0], "on", "off"),
+            ' ',
+            Twine::optional(&id, "?"),
+            ' ',
+            Twine::optional(&name, "<none>"),
+            ' ',
+            Twine::optional(&nested, "?"),
+        );
+        assert_eq!(t.to_string(), "false on -3 <none> nested");
+        assert_eq!(t.len(), 25);
+    }
 }
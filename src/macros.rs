@@ -0,0 +1,166 @@
+//! The `twine!` macro for building Twines from many pieces in place.
+
+use crate::Twine;
+
+/// Build a Twine from a mix of strs, chars, bools, integers, floats, format arguments and other Twines.
+///
+/// The pieces are concatenated from left to right.
+/// Each piece is borrowed by the Twine, so temporary values like `x + 1` are fine
+/// as long as the Twine is used in the same statement, e.g. passed to a function.
+///
+/// Use `twine!(let name = ...)` to bind the Twine to a local variable instead.
+/// The pieces are then kept alive in hidden local variables until the end of the block.
+///
+/// # Example
+/// ```
+/// # use twine::{twine, Twine};
+/// fn render(t: &Twine) -> String {
+///     t.to_string()
+/// }
+/// let name = "n";
+/// let n = 42u32;
+/// assert_eq!(render(&twine!(name, " = ", n, ';', n + 1)), "n = 42;43");
+///
+/// twine!(let line = "[", name, "]: ", 1.5f64);
+/// twine!(let log = line, " ", Twine::hex(&n));
+/// assert_eq!(log.to_string(), "[n]: 1.5 2a");
+/// ```
+#[macro_export]
+macro_rules! twine {
+    () => {
+        $crate::Twine::empty()
+    };
+    (let $name:ident = $($piece:expr),* $(,)?) => {
+        $crate::twine!(@let $name; ; $($piece),*);
+    };
+    (@let $name:ident; ;) => {
+        let $name = $crate::Twine::empty();
+    };
+    (@let $name:ident; ; $first:expr $(, $rest:expr)*) => {
+        let value = &$first;
+        let node = $crate::TwinePiece::to_twine(value);
+        $crate::twine!(@let $name; node; $($rest),*);
+    };
+    (@let $name:ident; $acc:ident;) => {
+        let $name = $acc;
+    };
+    (@let $name:ident; $acc:ident; $piece:expr $(, $rest:expr)*) => {
+        let value = &$piece;
+        let piece = $crate::TwinePiece::to_twine(value);
+        let node = &$acc + &piece;
+        $crate::twine!(@let $name; node; $($rest),*);
+    };
+    (@concat $acc:expr;) => {
+        $acc
+    };
+    (@concat $acc:expr; $piece:expr $(, $rest:expr)*) => {
+        $crate::twine!(@concat &$acc + &$crate::TwinePiece::to_twine(&$piece); $($rest),*)
+    };
+    ($first:expr $(, $rest:expr)* $(,)?) => {
+        $crate::twine!(@concat $crate::TwinePiece::to_twine(&$first); $($rest),*)
+    };
+}
+
+/// Values that can be borrowed as a Twine, like the pieces of the `twine!` macro.
+///
+/// Implemented for strs and owned strs, chars, bools, integers, floats, `fmt::Arguments`,
+/// Twines, `TwineBuf` and references to all of them.
+/// It is the bound of the values accepted by `Twine::join`, `Twine::optional` and `TwineArena::piece`.
+///
+/// # Example
+/// ```
+/// # use twine::{Twine, TwinePiece};
+/// fn labeled<T: TwinePiece>(label: &str, value: &T) -> String {
+///     Twine::from(label).concat(&value.to_twine()).to_string()
+/// }
+/// assert_eq!(labeled("id=", &42u32), "id=42");
+/// assert_eq!(labeled("name=", &"twine"), "name=twine");
+/// ```
+pub trait TwinePiece {
+    /// A Twine that borrows the value.
+    fn to_twine(&self) -> Twine<'_>;
+}
+
+impl<T: TwinePiece + ?Sized> TwinePiece for &T {
+    #[inline(always)]
+    fn to_twine(&self) -> Twine<'_> {
+        (**self).to_twine()
+    }
+}
+
+impl TwinePiece for Twine<'_> {
+    #[inline(always)]
+    fn to_twine(&self) -> Twine<'_> {
+        *self
+    }
+}
+
+impl TwinePiece for str {
+    #[inline(always)]
+    fn to_twine(&self) -> Twine<'_> {
+        Twine::from(self)
+    }
+}
+
+/// Implement `TwinePiece` for owned and shared strs
+macro_rules! impl_twine_piece_str {
+    ($($(#[$cfg:meta])* $t:ty),* $(,)?) => {
+        $(
+            $(#[$cfg])*
+            impl TwinePiece for $t {
+                #[inline(always)]
+                fn to_twine(&self) -> Twine<'_> {
+                    Twine::from(&**self)
+                }
+            }
+        )*
+    };
+}
+
+impl_twine_piece_str! {
+    #[cfg(feature = "alloc")]
+    alloc::string::String,
+    #[cfg(feature = "alloc")]
+    alloc::boxed::Box<str>,
+    #[cfg(feature = "alloc")]
+    alloc::borrow::Cow<'_, str>,
+    #[cfg(feature = "alloc")]
+    alloc::rc::Rc<str>,
+    #[cfg(all(feature = "alloc", target_has_atomic = "ptr"))]
+    alloc::sync::Arc<str>,
+}
+
+impl TwinePiece for char {
+    #[inline(always)]
+    fn to_twine(&self) -> Twine<'_> {
+        Twine::from(self)
+    }
+}
+
+impl TwinePiece for bool {
+    #[inline(always)]
+    fn to_twine(&self) -> Twine<'_> {
+        Twine::from(self)
+    }
+}
+
+impl TwinePiece for f32 {
+    #[inline(always)]
+    fn to_twine(&self) -> Twine<'_> {
+        Twine::float(self)
+    }
+}
+
+impl TwinePiece for f64 {
+    #[inline(always)]
+    fn to_twine(&self) -> Twine<'_> {
+        Twine::float(self)
+    }
+}
+
+impl TwinePiece for core::fmt::Arguments<'_> {
+    #[inline(always)]
+    fn to_twine(&self) -> Twine<'_> {
+        Twine::from(self)
+    }
+}
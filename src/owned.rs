@@ -0,0 +1,441 @@
+//! Owned Twines that can outlive the values they were built from.
+//!
+//! A `TwineBuf` stores the leaves of a Twine in rendering order.
+//! Strs and chars are copied into a single String, consecutive ones are merged.
+//! Integers and floats are copied by value, so they are still rendered lazily.
+//! Repeated Twines are copied once and repeated lazily as well.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::float::{FloatFormat, FloatRef};
+use crate::int::{IntFormat, IntRef};
+use crate::{Twine, TwineChild, TwineKind};
+
+/// An owned leaf of a `TwineBuf`.
+#[derive(Debug, Clone, Copy)]
+enum Leaf {
+    /// The text in the given range of the `TwineBuf`.
+    Text(usize, usize),
+    /// A negative decimal integer.
+    Signed(i128),
+    /// A non-negative decimal integer.
+    Unsigned(u128),
+    /// The digits of an integer in the given radix,
+    /// the two's complement bits in radix 2, 8 and 16.
+    Radix(u128, u8, IntFormat),
+    /// A negative integer in a radix other than 2, 8 and 16.
+    SignedRadix(i128, u8, IntFormat),
+    F32(f32, FloatFormat),
+    F64(f64, FloatFormat),
+    /// Repeats the leaves in the range `start..end`, which directly precede it,
+    /// the given number of times more.
+    Repeat(usize, usize, usize),
+}
+
+/// A leaf of a `TwineBuf` as visited by the iterators.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum BufLeaf<'a> {
+    Child(TwineChild<'a>),
+    /// Repeat the leaves in the range `start..end` the given number of times more.
+    Repeat(usize, usize, usize),
+}
+
+/// An owned, heap-backed Twine.
+///
+/// Use `TwineBuf::from(&twine)` to copy a Twine, e.g. to return it from a function or to store it in a struct,
+/// and `as_twine` to use it with every API that expects a Twine.
+///
+/// Only the structure needed for rendering is copied:
+/// all strs and chars are copied into a single String,
+/// integers and floats are copied by value, `fmt::Arguments`, `Display` and `Debug` values, closures, joins, paddings and truncations are rendered.
+/// Repeated Twines are copied once, no matter how often they are repeated.
+///
+/// # Example
+/// ```
+/// # use twine::{twine, Twine, TwineBuf};
+/// fn greeting(name: &str, visits: u32) -> TwineBuf {
+///     TwineBuf::from(&twine!("hello ", name, " #", visits))
+/// }
+/// let buf = greeting("world", 3);
+/// assert_eq!(buf.as_twine().to_string(), "hello world #3");
+/// let view = buf.as_twine();
+/// let exclamation = Twine::from("!");
+/// assert_eq!(view.concat(&exclamation).to_string(), "hello world #3!");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct TwineBuf {
+    text: String,
+    leaves: Vec<Leaf>,
+    null: bool,
+}
+
+impl TwineBuf {
+    /// Create a new empty TwineBuf.
+    #[inline(always)]
+    #[must_use]
+    pub fn new() -> Self {
+        TwineBuf::default()
+    }
+
+    /// A Twine that borrows the content of the TwineBuf.
+    ///
+    /// # Example
+    /// ```
+    /// # use twine::{Twine, TwineBuf};
+    /// let buf = TwineBuf::from(&Twine::from(("foo", "bar")));
+    /// assert_eq!(buf.as_twine().as_single_str(), Some("foobar"));
+    /// assert_eq!(TwineBuf::from(&Twine::null()).as_twine().is_null(), true);
+    /// ```
+    #[must_use]
+    pub fn as_twine(&self) -> Twine<'_> {
+        if self.null {
+            return Twine::null();
+        }
+        match self.leaves.as_slice() {
+            [] => Twine::empty(),
+            [leaf] => Twine(TwineKind::Unary(self.child(leaf))),
+            _ => Twine(TwineKind::Unary(TwineChild::Buf(self))),
+        }
+    }
+
+    /// The child of a Twine that borrows the leaf.
+    #[inline]
+    fn child<'a>(&'a self, leaf: &'a Leaf) -> TwineChild<'a> {
+        match leaf {
+            Leaf::Text(start, end) => TwineChild::Str(&self.text[*start..*end]),
+            Leaf::Signed(x) => TwineChild::Dec(IntRef::I128(x)),
+            Leaf::Unsigned(x) => TwineChild::Dec(IntRef::U128(x)),
+            Leaf::Radix(x, radix, format) => TwineChild::Radix(IntRef::U128(x), *radix, *format),
+            Leaf::SignedRadix(x, radix, format) => {
+                TwineChild::Radix(IntRef::I128(x), *radix, *format)
+            }
+            Leaf::F32(x, format) => TwineChild::Float(FloatRef::F32(x), *format),
+            Leaf::F64(x, format) => TwineChild::Float(FloatRef::F64(x), *format),
+            Leaf::Repeat(..) => unreachable!("repeats are expanded by the callers"),
+        }
+    }
+
+    /// Visit all leaves of the TwineBuf in rendering order.
+    pub(crate) fn try_for_each_leaf<'a, E>(
+        &'a self,
+        mut f: impl FnMut(&TwineChild<'a>) -> Result<(), E>,
+    ) -> Result<(), E> {
+        self.try_for_each_leaf_in(0, self.leaves.len(), &mut f)
+    }
+
+    /// Visit the leaves in the range `start..end` in rendering order.
+    fn try_for_each_leaf_in<'a, E>(
+        &'a self,
+        start: usize,
+        end: usize,
+        f: &mut impl FnMut(&TwineChild<'a>) -> Result<(), E>,
+    ) -> Result<(), E> {
+        self.leaves[start..end]
+            .iter()
+            .try_for_each(|leaf| match *leaf {
+                Leaf::Repeat(start, end, more) => {
+                    (0..more).try_for_each(|_| self.try_for_each_leaf_in(start, end, f))
+                }
+                ref leaf => f(&self.child(leaf)),
+            })
+    }
+
+    /// The sum of `measure` over the leaves in the range `start..end`,
+    /// multiplied for repeated leaves instead of visiting them again.
+    fn sum_in(
+        &self,
+        start: usize,
+        end: usize,
+        measure: &impl Fn(&TwineChild<'_>) -> usize,
+    ) -> usize {
+        self.leaves[start..end].iter().fold(0, |sum: usize, leaf| {
+            sum.saturating_add(match *leaf {
+                Leaf::Repeat(start, end, more) => {
+                    self.sum_in(start, end, measure).saturating_mul(more)
+                }
+                ref leaf => measure(&self.child(leaf)),
+            })
+        })
+    }
+
+    /// The exact length in bytes of the rendered TwineBuf, see `Twine::len`.
+    pub(crate) fn len(&self) -> usize {
+        // the leaves do not nest Twines, so they need no stack entries
+        self.sum_in(0, self.leaves.len(), &|child| child.len(0))
+    }
+
+    /// The estimated length in bytes of the rendered TwineBuf, see `Twine::estimated_capacity`.
+    pub(crate) fn estimated_capacity(&self) -> usize {
+        self.sum_in(0, self.leaves.len(), &|child| child.estimated_capacity(0))
+    }
+
+    /// The number of leaves of the TwineBuf.
+    pub(crate) fn leaf_count(&self) -> usize {
+        self.leaves.len()
+    }
+
+    /// The leaf at the given index.
+    pub(crate) fn leaf(&self, i: usize) -> BufLeaf<'_> {
+        match self.leaves[i] {
+            Leaf::Repeat(start, end, more) => BufLeaf::Repeat(start, end, more),
+            ref leaf => BufLeaf::Child(self.child(leaf)),
+        }
+    }
+
+    /// Append text, merging it with the previous leaf if that is text as well
+    /// and not before the index `sealed`.
+    fn push_text(&mut self, start: usize, sealed: usize) {
+        let end = self.text.len();
+        if start == end {
+            return;
+        }
+        let mergeable = self.leaves.len() > sealed;
+        match self.leaves.last_mut() {
+            Some(Leaf::Text(_, last_end)) if mergeable && *last_end == start => *last_end = end,
+            _ => self.leaves.push(Leaf::Text(start, end)),
+        }
+    }
+
+    /// Append a copy of a leaf child of a Twine.
+    ///
+    /// Text is not merged into the leaves before the index `sealed`.
+    /// Twines nested in the child are visited with at most `depth` stack entries.
+    fn push(&mut self, child: &TwineChild<'_>, sealed: usize, depth: usize) {
+        let start = self.text.len();
+        match child {
+            TwineChild::Str(s) => self.text.push_str(s),
+            TwineChild::Char(ch) => self.text.push(**ch),
+            // writing into a String only fails if a `Display` implementation fails
+            // or if a nested Twine is too deep, the part written until then is kept
+            TwineChild::FmtArgs(_)
+            | TwineChild::Display(_)
+            | TwineChild::Debug(_)
+            | TwineChild::Fn(_)
+            | TwineChild::Join(_)
+            | TwineChild::Pad(_)
+            | TwineChild::Truncate(_) => {
+                let _ = child.write_to(&mut self.text, depth);
+            }
+            // the leaves of the repeated Twine are copied once and push their own text,
+            // the range is sealed so that no text outside of it is merged into it
+            TwineChild::Repeat(t, count) => {
+                let first = self.leaves.len();
+                let _ = t.try_for_each_leaf(depth, (), |child| {
+                    self.push(child, first, crate::nested_depth(depth));
+                    Ok(())
+                });
+                let last = self.leaves.len();
+                if first < last {
+                    self.leaves.push(Leaf::Repeat(first, last, count - 1));
+                }
+                return;
+            }
+            // the leaves are copied as they are, so that repeated leaves are still repeated lazily,
+            // only text that does not start a repeated range is merged into the text before it
+            TwineChild::Buf(buf) => {
+                let merge = !buf
+                    .leaves
+                    .iter()
+                    .any(|leaf| matches!(leaf, Leaf::Repeat(0, ..)));
+                let mut offset = self.leaves.len();
+                for (i, leaf) in buf.leaves.iter().enumerate() {
+                    match *leaf {
+                        Leaf::Text(start, end) => {
+                            let at = self.text.len();
+                            self.text.push_str(&buf.text[start..end]);
+                            if i == 0 && merge {
+                                self.push_text(at, sealed);
+                                offset = self.leaves.len() - 1;
+                            } else {
+                                self.leaves.push(Leaf::Text(at, self.text.len()));
+                            }
+                        }
+                        Leaf::Repeat(start, end, more) => {
+                            self.leaves
+                                .push(Leaf::Repeat(start + offset, end + offset, more));
+                        }
+                        leaf => self.leaves.push(leaf),
+                    }
+                }
+                return;
+            }
+            TwineChild::Dec(x) => {
+                let (abs, negative) = x.unsigned_abs();
+                self.leaves.push(if negative {
+                    // the absolute value of a negative integer is at most 2^127
+                    Leaf::Signed(0i128.wrapping_sub_unsigned(abs))
+                } else {
+                    Leaf::Unsigned(abs)
+                });
+            }
+            TwineChild::Radix(x, radix, format) => {
+                let (n, negative) = x.radix_digits(*radix);
+                self.leaves.push(if negative {
+                    // the absolute value of a negative integer is at most 2^127
+                    Leaf::SignedRadix(0i128.wrapping_sub_unsigned(n), *radix, *format)
+                } else {
+                    Leaf::Radix(n, *radix, *format)
+                });
+            }
+            TwineChild::Float(FloatRef::F32(x), format) => {
+                self.leaves.push(Leaf::F32(**x, *format));
+            }
+            TwineChild::Float(FloatRef::F64(x), format) => {
+                self.leaves.push(Leaf::F64(**x, *format));
+            }
+            TwineChild::Twine(_) | TwineChild::Strs(_) | TwineChild::Slice(_) => {
+                unreachable!("only leaves are visited")
+            }
+        }
+        self.push_text(start, sealed);
+    }
+}
+
+impl From<&Twine<'_>> for TwineBuf {
+    /// Copy a Twine into a new TwineBuf.
+    ///
+    /// If the Twine is nested deeper than `Twine::MAX_DEPTH`,
+    /// only the part that could be visited is copied.
+    fn from(t: &Twine<'_>) -> Self {
+        let mut buf = TwineBuf {
+            null: t.is_null(),
+            ..TwineBuf::default()
+        };
+        if !buf.null {
+            let _ = t.try_for_each_leaf(Twine::MAX_DEPTH, (), |child| {
+                buf.push(child, 0, crate::nested_depth(Twine::MAX_DEPTH));
+                Ok(())
+            });
+        }
+        buf
+    }
+}
+
+impl core::fmt::Display for TwineBuf {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        self.as_twine().write_to(f)
+    }
+}
+
+impl crate::TwinePiece for TwineBuf {
+    #[inline(always)]
+    fn to_twine(&self) -> Twine<'_> {
+        self.as_twine()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::string::ToString;
+
+    #[test]
+    fn copies_all_leaves() {
+        let s = String::from("str");
+        let min = i128::MIN;
+        let max = u128::MAX;
+        let byte = -1i8;
+        let half = 0.5f32;
+        let third = 1.0f64 / 3.0;
+        let args = format_args!("<{}>", 42);
+        crate::twine!(
+            let t = s,
+            'ä',
+            "",
+            min,
+            max,
+            Twine::hex(&byte),
+            Twine::radix(&min, 36),
+            args,
+            half,
+            Twine::float_fixed(&third, 2),
+            Twine::from(&byte),
+        );
+        let expected = t.to_string();
+        let buf = TwineBuf::from(&t);
+        drop(s);
+        assert_eq!(buf.to_string(), expected);
+        assert_eq!(buf.as_twine().len(), expected.len());
+        assert_eq!(buf.leaves.len(), 9);
+    }
+
+    #[test]
+    fn merges_text() {
+        let c = 'c';
+        crate::twine!(let t = "a", "b", c, format_args!("d"));
+        let buf = TwineBuf::from(&t);
+        assert_eq!(buf.as_twine().as_single_str(), Some("abcd"));
+        assert!(TwineBuf::from(&Twine::empty()).as_twine().is_empty());
+        assert!(!TwineBuf::new().as_twine().is_null());
+    }
+
+    #[test]
+    fn nested_views() {
+        let n = 7u8;
+        let buf = TwineBuf::from(&crate::twine!("n=", n));
+        crate::twine!(let t = buf, ", ", buf);
+        let copy = TwineBuf::from(&t);
+        assert_eq!(copy.to_string(), "n=7, n=7");
+        assert_eq!(copy.leaves.len(), 4);
+        #[cfg(feature = "std")]
+        {
+            let mut bytes = std::vec::Vec::new();
+            t.write_io(&mut bytes).unwrap();
+            assert_eq!(bytes, b"n=7, n=7");
+        }
+    }
+
+    #[test]
+    fn copies_repeats() {
+        let n = 5u8;
+        crate::twine!(let cell = "|", n);
+        let row = Twine::repeat(&cell, 3);
+        let buf = TwineBuf::from(&row);
+        assert_eq!(buf.to_string(), "|5|5|5");
+        assert_eq!(buf.leaves.len(), 3);
+
+        // text around the repeated range is not merged into it
+        let dash = '-';
+        let rule = Twine::from(&dash);
+        let rules = Twine::repeat(&rule, 2);
+        let grid = Twine::repeat(&row, 2);
+        crate::twine!(let t = "[", rules, grid, "]");
+        let buf = TwineBuf::from(&t);
+        assert_eq!(buf.to_string(), "[--|5|5|5|5|5|5]");
+        assert_eq!(buf.as_twine().len(), 16);
+        assert_eq!(buf.leaves.len(), 8);
+
+        // copies of copies keep their repeated leaves
+        let view = buf.as_twine();
+        let copy = TwineBuf::from(&Twine::repeat(&view, 2));
+        assert_eq!(copy.to_string(), "[--|5|5|5|5|5|5][--|5|5|5|5|5|5]");
+        assert_eq!(copy.as_twine().len(), 32);
+        assert_eq!(copy.leaves.len(), 9);
+    }
+
+    #[test]
+    fn huge_repeats() {
+        let dash = '-';
+        let rule = Twine::from(&dash);
+        let huge = Twine::repeat(&rule, usize::MAX / 2);
+        let buf = TwineBuf::from(&huge);
+        assert_eq!(buf.text, "-");
+        assert_eq!(buf.leaves.len(), 2);
+        let view = buf.as_twine();
+        assert_eq!(view.len(), usize::MAX / 2);
+        assert_eq!(view.estimated_capacity(), usize::MAX / 2);
+        assert!(!view.is_empty());
+        assert!(view.chars().take(3).eq("---".chars()));
+        assert!(view.chars().rev().take(3).eq("---".chars()));
+
+        // copies of the view keep the repeat
+        crate::twine!(let t = "[", view, "]");
+        let copy = TwineBuf::from(&t);
+        assert_eq!(copy.text, "[-]");
+        assert_eq!(copy.leaves.len(), 4);
+        assert_eq!(copy.as_twine().len(), usize::MAX / 2 + 2);
+        assert!(copy.as_twine().chars().take(3).eq("[--".chars()));
+        assert!(copy.as_twine().chars().rev().take(3).eq("]--".chars()));
+    }
+}
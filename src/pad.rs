@@ -0,0 +1,277 @@
+//! Padding of Twines to a minimum width, e.g. to align the columns of a table.
+
+use crate::{Twine, TwineChild, TwineKind};
+
+/// Where the content of a padded Twine is placed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Align {
+    Left,
+    Right,
+    Center,
+}
+
+/// A Twine padded with a fill char to a minimum width,
+/// created by `Twine::pad_left`, `Twine::pad_right` and `Twine::center`.
+///
+/// Create a Twine of it with `Twine::from(&pad)`, see [views](crate#views).
+/// The width of the content is only measured when the Twine is measured or rendered,
+/// which renders the content once more.
+///
+/// The width is measured in chars by default,
+/// use `width_fn` to measure it differently, e.g. as the Unicode display width.
+///
+/// # Example
+/// ```
+/// # use twine::Twine;
+/// let (name, count) = ("apples", 12u32);
+/// let name = Twine::from(name);
+/// let count = Twine::from(&count);
+/// let name = Twine::pad_right(&name, 8, '.');
+/// let count = Twine::pad_left(&count, 4, ' ');
+/// let row = Twine::from(&name).concat(&Twine::from(&count)).to_string();
+/// assert_eq!(row, "apples..  12");
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Pad<'a> {
+    child: Twine<'a>,
+    width: usize,
+    fill: char,
+    align: Align,
+    width_fn: fn(&str) -> usize,
+}
+
+/// The number of chars in the str.
+fn char_count(s: &str) -> usize {
+    s.chars().count()
+}
+
+impl<'a> Twine<'a> {
+    /// Pad the Twine on the left with `fill` to at least `width`, aligning it to the right.
+    ///
+    /// # Example
+    /// ```
+    /// # use twine::Twine;
+    /// let n = -42i32;
+    /// let n = Twine::from(&n);
+    /// let pad = Twine::pad_left(&n, 6, '0');
+    /// assert_eq!(Twine::from(&pad).to_string(), "000-42");
+    /// let pad = Twine::pad_left(&n, 2, '0');
+    /// assert_eq!(Twine::from(&pad).to_string(), "-42");
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn pad_left(child: &Twine<'a>, width: usize, fill: char) -> Pad<'a> {
+        Pad::new(*child, width, fill, Align::Right)
+    }
+
+    /// Pad the Twine on the right with `fill` to at least `width`, aligning it to the left.
+    ///
+    /// # Example
+    /// ```
+    /// # use twine::Twine;
+    /// let name = Twine::from("ä");
+    /// let pad = Twine::pad_right(&name, 3, '_');
+    /// assert_eq!(Twine::from(&pad).to_string(), "ä__");
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn pad_right(child: &Twine<'a>, width: usize, fill: char) -> Pad<'a> {
+        Pad::new(*child, width, fill, Align::Left)
+    }
+
+    /// Pad the Twine on both sides with `fill` to at least `width`, centering it.
+    ///
+    /// If the padding can not be split evenly, the extra fill char is put on the right.
+    ///
+    /// # Example
+    /// ```
+    /// # use twine::Twine;
+    /// let title = Twine::from("abc");
+    /// let pad = Twine::center(&title, 8, '*');
+    /// assert_eq!(Twine::from(&pad).to_string(), "**abc***");
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn center(child: &Twine<'a>, width: usize, fill: char) -> Pad<'a> {
+        Pad::new(*child, width, fill, Align::Center)
+    }
+}
+
+impl<'a> Pad<'a> {
+    #[inline(always)]
+    fn new(child: Twine<'a>, width: usize, fill: char, align: Align) -> Self {
+        Pad {
+            child,
+            width,
+            fill,
+            align,
+            width_fn: char_count,
+        }
+    }
+
+    /// Measure the width of the content with `width_fn` instead of counting chars.
+    ///
+    /// The content is passed to `width_fn` in the pieces it is rendered in,
+    /// and the widths of all pieces are summed up.
+    /// The fill char is always counted as a width of 1.
+    ///
+    /// # Example
+    /// ```
+    /// # use twine::Twine;
+    /// /// Counts east asian wide chars twice
+    /// fn display_width(s: &str) -> usize {
+    ///     s.chars().map(|c| if ('\u{3000}'..='\u{9fff}').contains(&c) { 2 } else { 1 }).sum()
+    /// }
+    /// let word = Twine::from("日本");
+    /// let pad = Twine::pad_left(&word, 6, ' ').width_fn(display_width);
+    /// assert_eq!(Twine::from(&pad).to_string(), "  日本");
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn width_fn(mut self, width_fn: fn(&str) -> usize) -> Self {
+        self.width_fn = width_fn;
+        self
+    }
+
+    /// The number of fill chars on the left and on the right of the content.
+    ///
+    /// Fails if the content needs more than `depth` stack entries.
+    fn padding(&self, depth: usize) -> Result<(usize, usize), core::fmt::Error> {
+        /// Sums up the width of the strs written to it
+        struct WidthCounter(usize, fn(&str) -> usize);
+        impl core::fmt::Write for WidthCounter {
+            fn write_str(&mut self, s: &str) -> core::fmt::Result {
+                self.0 += (self.1)(s);
+                Ok(())
+            }
+        }
+        if self.width == 0 {
+            return Ok((0, 0));
+        }
+        let mut w = WidthCounter(0, self.width_fn);
+        self.child.write_to_within(&mut w, depth)?;
+        let padding = self.width.saturating_sub(w.0);
+        Ok(match self.align {
+            Align::Left => (0, padding),
+            Align::Right => (padding, 0),
+            Align::Center => (padding / 2, padding - padding / 2),
+        })
+    }
+
+    /// The fill char, the number of fill chars on the left, the content
+    /// and the number of fill chars on the right, e.g. to iterate them one by one.
+    pub(crate) fn split(
+        &self,
+        depth: usize,
+    ) -> Result<(char, usize, &Twine<'a>, usize), core::fmt::Error> {
+        let (left, right) = self.padding(depth)?;
+        Ok((self.fill, left, &self.child, right))
+    }
+
+    pub(crate) fn is_empty(&self, depth: usize) -> bool {
+        self.child.is_empty_within(depth) && self.padding(depth) == Ok((0, 0))
+    }
+
+    pub(crate) fn len(&self, depth: usize) -> usize {
+        let padding = self
+            .padding(depth)
+            .map_or(0, |(left, right)| (left + right) * self.fill.len_utf8());
+        self.child.len_within(depth) + padding
+    }
+
+    pub(crate) fn write_to(
+        &self,
+        mut w: &mut dyn core::fmt::Write,
+        depth: usize,
+    ) -> core::fmt::Result {
+        let (left, right) = self.padding(depth)?;
+        (0..left).try_for_each(|_| w.write_char(self.fill))?;
+        self.child.write_to_within(&mut w, depth)?;
+        (0..right).try_for_each(|_| w.write_char(self.fill))
+    }
+}
+
+impl<'a> From<&'a Pad<'a>> for Twine<'a> {
+    #[inline(always)]
+    fn from(pad: &'a Pad<'a>) -> Twine<'a> {
+        Twine(TwineKind::Unary(TwineChild::Pad(pad)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::string::ToString;
+
+    #[test]
+    fn alignments() {
+        let x = 255u8;
+        let hex = Twine::hex(&x);
+        let prefix = Twine::from("0x");
+        let value = prefix.concat(&hex);
+        for (pad, expected) in [
+            (Twine::pad_left(&value, 6, ' '), "  0xff"),
+            (Twine::pad_right(&value, 6, ' '), "0xff  "),
+            (Twine::center(&value, 7, '-'), "-0xff--"),
+            (Twine::center(&value, 3, '-'), "0xff"),
+            (Twine::pad_left(&value, 5, '·'), "·0xff"),
+        ] {
+            let t = Twine::from(&pad);
+            assert_eq!(t.to_string(), expected);
+            assert_eq!(t.len(), expected.len());
+            assert_eq!(t.estimated_capacity(), expected.len());
+            #[cfg(feature = "alloc")]
+            {
+                let copy = crate::TwineBuf::from(&t);
+                assert_eq!(copy.to_string(), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn empty_content() {
+        let empty = Twine::empty();
+        let pad = Twine::pad_left(&empty, 0, ' ');
+        assert!(Twine::from(&pad).is_empty());
+        let pad = Twine::center(&empty, 2, ' ');
+        assert!(!Twine::from(&pad).is_empty());
+        assert_eq!(Twine::from(&pad).to_string(), "  ");
+    }
+
+    #[test]
+    fn nested_too_deep() {
+        // every padding costs the Twines nested in it `NESTED_COST` stack entries
+        const VIEWS: usize = Twine::MAX_DEPTH / crate::NESTED_COST - 1;
+        let run = || {
+            let bump = bumpalo::Bump::new();
+            let x: &Twine = bump.alloc(Twine::from("x"));
+            let mut t = x;
+            let mut expected = std::string::String::from("x");
+            for _ in 0..VIEWS {
+                // nested left children are traversed with an explicit stack
+                let content: &Twine = bump.alloc(t.concat(x));
+                let content: &Twine = bump.alloc(content.concat(x));
+                let pad: &Pad = bump.alloc(Twine::pad_left(content, 0, ' '));
+                t = bump.alloc(Twine::from(pad));
+                expected.push_str("xx");
+            }
+            assert_eq!(t.to_string(), expected);
+            assert_eq!(t.len(), expected.len());
+            assert!(t.chars().rev().eq(expected.chars().rev()));
+            let pad: &Pad = bump.alloc(Twine::pad_left(t, 0, ' '));
+            let too_deep = Twine::from(pad);
+            let mut s = std::string::String::new();
+            assert!(too_deep.write_to(&mut s).is_err());
+            assert!(too_deep.len() < expected.len());
+            assert!(!too_deep.is_empty());
+        };
+        // the deepest Twines are rendered on small stacks as well
+        let stack_size = if cfg!(debug_assertions) { 128 } else { 64 } * 1024;
+        std::thread::Builder::new()
+            .stack_size(stack_size)
+            .spawn(run)
+            .unwrap()
+            .join()
+            .unwrap();
+    }
+}
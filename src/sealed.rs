@@ -0,0 +1,19 @@
+//! Supertraits that seal the public `Integer` and `Float` traits.
+//!
+//! They and the `IntRef` and `FloatRef` types they return are public only to be nameable by the sealed traits,
+//! this module is not reachable from outside of this crate, so the sealed traits can not be implemented there.
+
+use crate::float::FloatRef;
+use crate::int::IntRef;
+
+/// Prevents implementations of `Integer` outside of this crate.
+pub trait Integer {
+    /// Reference the integer for use in a `TwineChild`.
+    fn int_ref(&self) -> IntRef<'_>;
+}
+
+/// Prevents implementations of `Float` outside of this crate.
+pub trait Float {
+    /// Reference the float for use in a `TwineChild`.
+    fn float_ref(&self) -> FloatRef<'_>;
+}
@@ -0,0 +1,336 @@
+//! Truncation of Twines to a maximum length, e.g. to cap log lines.
+
+use core::fmt::Write;
+
+use crate::{Twine, TwineChild, TwineKind, WriteCounter};
+
+/// The unit the maximum length of a truncated Twine is measured in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Unit {
+    Bytes,
+    Chars,
+}
+
+impl Unit {
+    /// The longest prefix of `s` with at most `n` units that does not split a char,
+    /// and the number of units in it.
+    fn prefix(self, s: &str, n: usize) -> (&str, usize) {
+        match self {
+            Unit::Bytes => {
+                let mut end = n.min(s.len());
+                while !s.is_char_boundary(end) {
+                    end -= 1;
+                }
+                (&s[..end], end)
+            }
+            Unit::Chars => match s.char_indices().nth(n) {
+                Some((end, _)) => (&s[..end], n),
+                None => (s, s.chars().count()),
+            },
+        }
+    }
+}
+
+/// A Twine cut off at a maximum length, followed by an ellipsis if it was cut off,
+/// created by `Twine::truncate`.
+///
+/// Create a Twine of it with `Twine::from(&truncate)`, see [views](crate#views).
+///
+/// The rendered Twine, including the ellipsis, is never longer than the maximum length,
+/// unless the ellipsis alone is longer than that.
+/// The maximum length is measured in bytes by default, use `chars` to measure it in chars instead.
+/// Chars are never split.
+///
+/// Rendering stops as soon as the content is known to be too long,
+/// so the remaining children are not rendered at all.
+/// If the content is just a bit shorter than the maximum length, so that it only fits without the ellipsis,
+/// the part that could have been replaced by the ellipsis is rendered once more.
+///
+/// # Example
+/// ```
+/// # use twine::Twine;
+/// let id = 123456789u64;
+/// let label = Twine::from("request ");
+/// let id = Twine::from(&id);
+/// let line = label.concat(&id);
+/// let short = Twine::truncate(&line, 12, "...");
+/// assert_eq!(Twine::from(&short).to_string(), "request 1...");
+/// assert_eq!(short.is_truncated(), true);
+/// let long = Twine::truncate(&line, 17, "...");
+/// assert_eq!(Twine::from(&long).to_string(), "request 123456789");
+/// assert_eq!(long.is_truncated(), false);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Truncate<'a> {
+    child: Twine<'a>,
+    max: usize,
+    ellipsis: Twine<'a>,
+    unit: Unit,
+}
+
+/// Forwards the content up to the length that still leaves room for the ellipsis,
+/// then only counts the content until it exceeds the maximum length.
+struct Limit<'w> {
+    w: &'w mut dyn Write,
+    unit: Unit,
+    keep: usize,
+    max: usize,
+    /// Units written to `w`.
+    written: usize,
+    /// Bytes written to `w`.
+    written_bytes: usize,
+    /// Units counted after the written ones.
+    held: usize,
+    truncated: bool,
+}
+
+impl Write for Limit<'_> {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        let mut rest = s;
+        if self.held == 0 {
+            let (head, n) = self.unit.prefix(rest, self.keep - self.written);
+            self.w.write_str(head)?;
+            self.written += n;
+            self.written_bytes += head.len();
+            rest = &rest[head.len()..];
+            if rest.is_empty() {
+                return Ok(());
+            }
+        }
+        let (head, n) = self.unit.prefix(rest, self.max - self.written - self.held);
+        self.held += n;
+        if head.len() < rest.len() {
+            // stop rendering the remaining children
+            self.truncated = true;
+            return Err(core::fmt::Error);
+        }
+        Ok(())
+    }
+}
+
+/// Forwards everything after the first `skip` bytes.
+struct Skip<'w> {
+    w: &'w mut dyn Write,
+    skip: usize,
+}
+
+impl Write for Skip<'_> {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        if self.skip >= s.len() {
+            self.skip -= s.len();
+            return Ok(());
+        }
+        let rest = &s[self.skip..];
+        self.skip = 0;
+        self.w.write_str(rest)
+    }
+}
+
+/// Accepts and discards everything.
+struct Sink;
+
+impl Write for Sink {
+    fn write_str(&mut self, _: &str) -> core::fmt::Result {
+        Ok(())
+    }
+}
+
+impl<'a> Twine<'a> {
+    /// Cut the Twine off after `max` bytes and end it with `ellipsis` if it was cut off.
+    ///
+    /// # Example
+    /// ```
+    /// # use twine::Twine;
+    /// let word = Twine::from("äöü");
+    /// let bytes = Twine::truncate(&word, 5, "");
+    /// assert_eq!(Twine::from(&bytes).to_string(), "äö");
+    /// let chars = Twine::truncate(&word, 2, "…").chars();
+    /// assert_eq!(Twine::from(&chars).to_string(), "ä…");
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn truncate(child: &Twine<'a>, max: usize, ellipsis: impl Into<Twine<'a>>) -> Truncate<'a> {
+        Truncate {
+            child: *child,
+            max,
+            ellipsis: ellipsis.into(),
+            unit: Unit::Bytes,
+        }
+    }
+}
+
+impl<'a> Truncate<'a> {
+    /// Measure the maximum length in chars instead of bytes.
+    #[inline]
+    #[must_use]
+    pub fn chars(mut self) -> Self {
+        self.unit = Unit::Chars;
+        self
+    }
+
+    /// Check if the content is longer than the maximum length and is cut off.
+    ///
+    /// The content is rendered until it is known to be too long.
+    #[must_use]
+    pub fn is_truncated(&self) -> bool {
+        self.write_to(&mut Sink).unwrap_or(false)
+    }
+
+    /// Render the truncated Twine and report if the content was cut off.
+    ///
+    /// # Errors
+    ///
+    /// This method returns an `fmt::Error` Error Result if writing to `w` fails
+    /// or if the Twine is nested deeper than `Twine::MAX_DEPTH`.
+    ///
+    /// # Example
+    /// ```
+    /// # use twine::Twine;
+    /// let n = 1234u32;
+    /// let n = Twine::from(&n);
+    /// let mut s = String::new();
+    /// assert_eq!(Twine::truncate(&n, 3, "+").write_to(&mut s), Ok(true));
+    /// assert_eq!(s, "12+");
+    /// ```
+    pub fn write_to<W: Write>(&self, w: &mut W) -> Result<bool, core::fmt::Error> {
+        self.render(w, Twine::MAX_DEPTH)
+    }
+
+    /// Write the content to `w` up to the length that leaves room for the ellipsis
+    /// and find out if the rest of it fits without the ellipsis.
+    fn limit<'w>(&self, w: &'w mut dyn Write, depth: usize) -> Result<Limit<'w>, core::fmt::Error> {
+        /// Counts the units written to it
+        struct Counter(Unit, usize);
+        impl Write for Counter {
+            fn write_str(&mut self, s: &str) -> core::fmt::Result {
+                self.1 += self.0.prefix(s, s.len()).1;
+                Ok(())
+            }
+        }
+        let mut ellipsis = Counter(self.unit, 0);
+        self.ellipsis.write_to_within(&mut ellipsis, depth)?;
+        let mut limit = Limit {
+            w,
+            unit: self.unit,
+            keep: self.max.saturating_sub(ellipsis.1),
+            max: self.max,
+            written: 0,
+            written_bytes: 0,
+            held: 0,
+            truncated: false,
+        };
+        match self.child.write_to_within(&mut limit, depth) {
+            Err(e) if !limit.truncated => Err(e),
+            _ => Ok(limit),
+        }
+    }
+
+    /// Render the truncated Twine, failing if it needs more than `depth` stack entries.
+    pub(crate) fn render(&self, w: &mut dyn Write, depth: usize) -> Result<bool, core::fmt::Error> {
+        let mut limit = self.limit(w, depth)?;
+        if limit.truncated {
+            self.ellipsis.write_to_within(&mut limit.w, depth)?;
+            Ok(true)
+        } else if limit.held == 0 {
+            Ok(false)
+        } else {
+            // the content fits without the ellipsis after all
+            let mut skip = Skip {
+                w: limit.w,
+                skip: limit.written_bytes,
+            };
+            self.child.write_to_within(&mut skip, depth)?;
+            Ok(false)
+        }
+    }
+
+    /// The content, the ellipsis and, if the content is cut off, the number of bytes of it that are kept,
+    /// e.g. to iterate them one by one.
+    pub(crate) fn split(
+        &self,
+        depth: usize,
+    ) -> Result<(&Twine<'a>, &Twine<'a>, Option<usize>), core::fmt::Error> {
+        let mut sink = Sink;
+        let limit = self.limit(&mut sink, depth)?;
+        let kept = limit.truncated.then_some(limit.written_bytes);
+        Ok((&self.child, &self.ellipsis, kept))
+    }
+
+    pub(crate) fn is_empty(&self, depth: usize) -> bool {
+        self.len(depth) == 0
+    }
+
+    pub(crate) fn len(&self, depth: usize) -> usize {
+        let mut w = WriteCounter(0);
+        let _ = self.render(&mut w, depth);
+        w.0
+    }
+}
+
+impl<'a> From<&'a Truncate<'a>> for Twine<'a> {
+    #[inline(always)]
+    fn from(truncate: &'a Truncate<'a>) -> Twine<'a> {
+        Twine(TwineKind::Unary(TwineChild::Truncate(truncate)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::string::{String, ToString};
+
+    #[test]
+    fn limits() {
+        let name = "größe";
+        let value = 42u16;
+        crate::twine!(let t = name, '=', value);
+        for (max, ellipsis, chars, expected, truncated) in [
+            (0, "", false, "", true),
+            (3, "", false, "gr", true),
+            (4, "", false, "grö", true),
+            (4, ".", false, "gr.", true),
+            (9, "..", false, "größe..", true),
+            (10, "..", false, "größe=42", false),
+            (11, "..", false, "größe=42", false),
+            (7, "..", true, "größe..", true),
+            (8, "..", true, "größe=42", false),
+            (2, "...", false, "...", true),
+        ] {
+            let mut truncate = Twine::truncate(&t, max, ellipsis);
+            if chars {
+                truncate = truncate.chars();
+            }
+            let mut s = String::new();
+            assert_eq!(truncate.write_to(&mut s), Ok(truncated));
+            assert_eq!(s, expected);
+            assert_eq!(truncate.is_truncated(), truncated);
+            let t = Twine::from(&truncate);
+            assert_eq!(t.to_string(), expected);
+            assert_eq!(t.len(), expected.len());
+            assert_eq!(t.is_empty(), expected.is_empty());
+        }
+    }
+
+    #[test]
+    fn short_circuits() {
+        use core::cell::Cell;
+        /// Counts how often it is rendered
+        struct Counted<'c>(&'c Cell<usize>);
+        impl core::fmt::Display for Counted<'_> {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                self.0.set(self.0.get() + 1);
+                f.write_str("counted")
+            }
+        }
+        let calls = Cell::new(0);
+        let counted = Counted(&calls);
+        let args = format_args!("{counted}");
+        let long = "x".repeat(100);
+        let head = Twine::from(long.as_str());
+        let tail = Twine::from(&args);
+        let t = head.concat(&tail);
+        let truncate = Twine::truncate(&t, 10, "...");
+        assert_eq!(Twine::from(&truncate).to_string(), "xxxxxxx...");
+        assert_eq!(calls.get(), 0);
+    }
+}